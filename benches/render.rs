@@ -0,0 +1,114 @@
+// Compares the allocating and the frame buffer render paths. Run with `cargo bench`.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use textmenu::menu::Menu;
+use textmenu::menu_items::basic_menu_item::BasicMenuItem;
+use textmenu::menu_items::list_menu_item::{ListEditMode, ListMenuItem};
+use textmenu::menu_items::menu_item::MenuItem;
+use textmenu::menu_items::range_menu_item::{RangeEditMode, RangeMenuItem};
+use textmenu::menu_items::toggle_menu_item::ToggleMenuItem;
+use textmenu::renderer::frame_buffer::FrameBuffer;
+use textmenu::renderer::layout::Renderer;
+use textmenu::renderer::marquee::Marquee;
+use textmenu::renderer::string_renderer::StringRenderer;
+
+const FRAME_COUNT: usize = 100_000;
+
+struct CountingAllocator;
+
+static ALLOCATION_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATION_COUNT.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATION_COUNT.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn measure(name: &str, mut render: impl FnMut(usize)) {
+    let allocation_count = ALLOCATION_COUNT.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for tick in 0..FRAME_COUNT {
+        render(tick);
+    }
+    let elapsed = start.elapsed();
+    let allocation_count = ALLOCATION_COUNT.load(Ordering::Relaxed) - allocation_count;
+    println!(
+        "{:<12} {:>8.0} ns/frame {:>6.1} allocations/frame",
+        name,
+        elapsed.as_nanos() as f64 / FRAME_COUNT as f64,
+        allocation_count as f64 / FRAME_COUNT as f64
+    );
+}
+
+fn measure_menu(name: &str, menu: &Menu, renderer: &StringRenderer) {
+    let mut frame: FrameBuffer<16, 2> = FrameBuffer::new();
+    println!("{}", name);
+    measure("render", |tick| {
+        black_box(renderer.render_frame(black_box(menu), tick));
+    });
+    measure("render_into", |tick| {
+        renderer
+            .render_into(black_box(menu), tick, &mut frame)
+            .unwrap();
+        black_box(&frame);
+    });
+}
+
+fn main() {
+    let items: Vec<Box<dyn MenuItem>> = vec![
+        Box::new(BasicMenuItem::new(String::from("Status"))),
+        Box::new(ToggleMenuItem::new(String::from("Backlight"))),
+        Box::new(ToggleMenuItem::new(String::from("Sound"))),
+        Box::new(BasicMenuItem::new(String::from("About"))),
+    ];
+    let menu = Menu::new(items).unwrap();
+    let mut renderer = StringRenderer::new(16, 2).unwrap();
+    measure_menu("basic and toggle items", &menu, &renderer);
+
+    let mode_entries = vec![String::from("Auto"), String::from("Manual")];
+    let mut picker_item = ListMenuItem::new(
+        String::from("Language"),
+        vec![String::from("English"), String::from("Deutsch")],
+    )
+    .unwrap();
+    picker_item.set_edit_mode(ListEditMode::Picker);
+    let items: Vec<Box<dyn MenuItem>> = vec![
+        Box::new(ListMenuItem::new(String::from("Mode"), mode_entries).unwrap()),
+        Box::new(picker_item),
+    ];
+    let mut menu = Menu::new(items).unwrap();
+    measure_menu("list items", &menu, &renderer);
+    menu.down();
+    menu.enter();
+    measure_menu("open list picker", &menu, &renderer);
+
+    // Focused digit editing, scrolled by the marquee
+    let mut range_item: RangeMenuItem =
+        RangeMenuItem::new(String::from("Frequency"), 0, 999_999, 1).unwrap();
+    range_item.set_edit_mode(RangeEditMode::Digits).unwrap();
+    range_item.set_unit(String::from(" Hz"));
+    let items: Vec<Box<dyn MenuItem>> = vec![
+        Box::new(range_item),
+        Box::new(BasicMenuItem::new(String::from("About"))),
+    ];
+    let mut menu = Menu::new(items).unwrap();
+    menu.enter();
+    renderer.set_marquee(Marquee::new(4, 8).unwrap());
+    measure_menu("focused range item", &menu, &renderer);
+}
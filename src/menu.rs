@@ -4,6 +4,7 @@ use crate::menu_items::submenu_menu_item::SubmenuMenuItem;
 
 pub struct Menu {
     submenu_menu_item: SubmenuMenuItem,
+    generation: usize,
 }
 
 #[derive(Debug)]
@@ -17,6 +18,7 @@ impl Menu {
             .map_err(|_| NewError::InvalidItemsLength)?;
         Ok(Menu {
             submenu_menu_item: submenu,
+            generation: 0,
         })
     }
 
@@ -24,8 +26,39 @@ impl Menu {
         &self.submenu_menu_item
     }
 
+    // Changes whenever something visible may have changed, compare it to the generation of
+    // the last drawn frame to know if a redraw is needed
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    // For changes the menu can't see, e.g. a bound value written by the application
+    pub fn invalidate(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    // Unhandled presses can change the screen as well, e.g. an item giving up its focus
     pub fn press(&mut self, key: KeyboardKey) -> bool {
-        self.submenu_menu_item.press(&key, true).handled
+        let selection_state = self.get_selection_state();
+        let handled = self.submenu_menu_item.press(&key, true).handled;
+        if handled || self.get_selection_state() != selection_state {
+            self.invalidate();
+        }
+        handled
+    }
+
+    // Focus and selected item idx of every submenu on the selected path, outermost first
+    fn get_selection_state(&self) -> Vec<(bool, usize)> {
+        let mut selection_state = Vec::new();
+        let mut submenu = Some(&self.submenu_menu_item);
+        while let Some(current_submenu) = submenu {
+            selection_state.push((
+                current_submenu.is_focused(),
+                current_submenu.get_selected_item_idx(),
+            ));
+            submenu = current_submenu.get_selected_item().get_child_submenu();
+        }
+        selection_state
     }
 
     pub fn enter(&mut self) -> bool {
@@ -51,6 +84,15 @@ impl Menu {
     pub fn back(&mut self) -> bool {
         self.press(KeyboardKey::new(Some(FunctionKey::BACK), None))
     }
+
+    // Polls all live items, returns true if the menu has to be redrawn
+    pub fn update(&mut self) -> bool {
+        let changed = self.submenu_menu_item.update();
+        if changed {
+            self.invalidate();
+        }
+        changed
+    }
 }
 
 #[cfg(test)]
@@ -58,9 +100,12 @@ mod tests {
     use super::*;
     use crate::menu_items::action_menu_item::ActionMenuItem;
     use crate::menu_items::basic_menu_item::BasicMenuItem;
-    use crate::menu_items::list_menu_item::ListMenuItem;
+    use crate::menu_items::list_menu_item::{ListEditMode, ListMenuItem};
+    use crate::menu_items::overflow_policy::OverflowPolicy;
     use crate::menu_items::range_menu_item::RangeMenuItem;
-    use crate::menu_items::toggle_menu_item::ToggleMenuItem;
+    use crate::menu_items::toggle_menu_item::{ToggleMenuItem, ToggleMode};
+    use crate::menu_items::value_menu_item::ValueMenuItem;
+    use std::cell::Cell;
     use std::cell::RefCell;
     use std::rc::Rc;
 
@@ -69,7 +114,7 @@ mod tests {
         expected_item_count: usize,
         expected_idx: usize,
         expected_is_focused: bool,
-    ) -> () {
+    ) {
         assert_eq!(submenu.item_count(), expected_item_count);
         assert_eq!(submenu.get_selected_item_idx(), expected_idx);
         assert_eq!(submenu.is_focused(), expected_is_focused);
@@ -80,7 +125,7 @@ mod tests {
         expected_item_count: usize,
         expected_idx: usize,
         expected_is_focused: bool,
-    ) -> () {
+    ) {
         let submenu = menu.get_submenu_menu_item();
         assert_submenu(
             submenu,
@@ -114,19 +159,19 @@ mod tests {
         let mut menu = Menu::new(items).unwrap();
         assert_submenu_state(&menu, 8, 0, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 8, 1, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 8, 2, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 8, 3, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 8, 4, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 8, 5, false);
     }
 
@@ -143,22 +188,22 @@ mod tests {
 
         assert_submenu_state(&menu, 5, 0, false);
 
-        assert_eq!(menu.up(), false);
+        assert!(!menu.up());
         assert_submenu_state(&menu, 5, 0, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 5, 1, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 5, 2, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 5, 3, false);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 5, 4, false);
 
-        assert_eq!(menu.down(), false);
+        assert!(!menu.down());
         assert_submenu_state(&menu, 5, 4, false);
     }
 
@@ -170,7 +215,7 @@ mod tests {
 
         assert_submenu_state(&menu, 1, 0, false);
 
-        assert_eq!(menu.enter(), false);
+        assert!(!menu.enter());
         assert_submenu_state(&menu, 1, 0, false);
     }
 
@@ -195,7 +240,7 @@ mod tests {
         assert_submenu_state(&menu, 2, 0, false);
         assert_eq!(*clicked_count.borrow(), 0);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_eq!(*clicked_count.borrow(), 1);
     }
 
@@ -208,7 +253,10 @@ mod tests {
     ) {
         assert_eq!(item.get_label(is_focused), expected_label);
         assert_eq!(item.get_selected_entry_idx(), expected_selected_entry_idx);
-        assert_eq!(item.get_selected_entry(), expected_selected_entry);
+        assert_eq!(
+            item.get_selected_entry().as_deref(),
+            Some(expected_selected_entry)
+        );
     }
 
     fn assert_focused_list_menu_item_state(
@@ -249,30 +297,30 @@ mod tests {
         assert_submenu_state(&menu, 2, 0, false);
         assert_focused_list_menu_item_state(&menu, "Item1: Elem1", 0, "Elem1");
 
-        assert_eq!(menu.left(), false);
-        assert_eq!(menu.right(), false);
+        assert!(!menu.left());
+        assert!(!menu.right());
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_list_menu_item_state(&menu, "Item1: Elem1", 0, "Elem1");
 
         // Can't move while focused
-        assert_eq!(menu.up(), false);
-        assert_eq!(menu.down(), false);
+        assert!(!menu.up());
+        assert!(!menu.down());
 
-        assert_eq!(menu.right(), true);
+        assert!(menu.right());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_list_menu_item_state(&menu, "Item1: Elem2", 0, "Elem1");
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         assert_submenu_state(&menu, 2, 0, false);
         assert_focused_list_menu_item_state(&menu, "Item1: Elem1", 0, "Elem1");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_list_menu_item_state(&menu, "Item1: Elem1", 0, "Elem1");
 
-        assert_eq!(menu.left(), true);
+        assert!(menu.left());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_list_menu_item_state(&menu, "Item1: Elem3", 0, "Elem1");
 
@@ -326,15 +374,38 @@ mod tests {
         assert_focused_toggle_menu_item_state(&menu, "Item1: OFF", false);
         assert_submenu_state(&menu, 2, 0, false);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, false);
         assert_focused_toggle_menu_item_state(&menu, "Item1: ON", true);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, false);
         assert_focused_toggle_menu_item_state(&menu, "Item1: OFF", false);
     }
 
+    #[test]
+    fn staged_toggle_item_is_usable() {
+        let mut toggle_menu_item = ToggleMenuItem::new(String::from("Item1"));
+        toggle_menu_item.set_mode(ToggleMode::Staged);
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(toggle_menu_item),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+        let mut menu = Menu::new(items).unwrap();
+
+        assert!(menu.enter());
+        assert_submenu_state(&menu, 2, 0, true);
+        assert_focused_toggle_menu_item_state(&menu, "Item1: OFF", false);
+
+        assert!(!menu.down());
+        assert!(menu.right());
+        assert_focused_toggle_menu_item_state(&menu, "Item1: ON", false);
+
+        assert!(menu.enter());
+        assert_submenu_state(&menu, 2, 0, false);
+        assert_focused_toggle_menu_item_state(&menu, "Item1: ON", true);
+    }
+
     fn get_required_range(item: &dyn MenuItem) -> &RangeMenuItem {
         if let Some(range_menu_item) = item.as_any().downcast_ref::<RangeMenuItem>() {
             range_menu_item
@@ -361,7 +432,7 @@ mod tests {
     #[test]
     fn range_item_is_usable() {
         let items: Vec<Box<dyn MenuItem>> = vec![
-            Box::new(RangeMenuItem::new(String::from("Item1"), 3, 10, 1).unwrap()),
+            Box::new(RangeMenuItem::<u32>::new(String::from("Item1"), 3, 10, 1).unwrap()),
             Box::new(BasicMenuItem::new(String::from("Item2"))),
         ];
         let mut menu = Menu::new(items).unwrap();
@@ -369,27 +440,50 @@ mod tests {
         assert_submenu_state(&menu, 2, 0, false);
         assert_focused_range_menu_item_state(&menu, "Item1: 3", 3);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_range_menu_item_state(&menu, "Item1: 3", 3);
 
-        assert_eq!(menu.left(), true);
+        assert!(menu.left());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_range_menu_item_state(&menu, "Item1: 10", 3);
 
-        assert_eq!(menu.left(), true);
+        assert!(menu.left());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_range_menu_item_state(&menu, "Item1: 9", 3);
 
-        assert_eq!(menu.right(), true);
+        assert!(menu.right());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_range_menu_item_state(&menu, "Item1: 10", 3);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, false);
         assert_focused_range_menu_item_state(&menu, "Item1: 10", 10);
     }
 
+    #[test]
+    fn clamped_range_item_reports_boundary() {
+        let mut range_menu_item =
+            RangeMenuItem::<u32>::new(String::from("Item1"), 0, 2, 1).unwrap();
+        range_menu_item.set_overflow_policy(OverflowPolicy::Clamp);
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(range_menu_item),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+        let mut menu = Menu::new(items).unwrap();
+
+        assert!(menu.enter());
+        assert!(!menu.left());
+        assert_submenu_state(&menu, 2, 0, true);
+        assert_focused_range_menu_item_state(&menu, "Item1: 0", 0);
+
+        assert!(menu.right());
+        assert!(menu.right());
+        assert!(!menu.right());
+        assert_submenu_state(&menu, 2, 0, true);
+        assert_focused_range_menu_item_state(&menu, "Item1: 2", 0);
+    }
+
     fn get_required_submenu(item: &dyn MenuItem) -> &SubmenuMenuItem {
         if let Some(submenu_menu_item) = item.as_any().downcast_ref::<SubmenuMenuItem>() {
             submenu_menu_item
@@ -469,29 +563,149 @@ mod tests {
 
         assert_submenu_state(&menu, 2, 0, false);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_submenu_menu_item_state(&menu, 2, 0, false);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_submenu_menu_item_state(&menu, 2, 0, false);
         assert_focused_lvl2_toggle_menu_item_state(&menu, "Sub Item1: ON", true);
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_submenu_menu_item_state(&menu, 2, 1, false);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_submenu_menu_item_state(&menu, 2, 1, true);
         assert_focused_lvl2_submenu_menu_item_state(&menu, 1, 0, false);
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         assert_submenu_state(&menu, 2, 0, true);
         assert_focused_submenu_menu_item_state(&menu, 2, 1, false);
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         assert_submenu_state(&menu, 2, 0, false);
     }
+
+    #[test]
+    fn update_reports_changed_value_items() {
+        let temperature = Rc::new(Cell::new(21));
+        let temperature_clone = Rc::clone(&temperature);
+        let value_item: ValueMenuItem<i32> = ValueMenuItem::new(
+            String::from("Temp"),
+            Box::new(move || temperature_clone.get()),
+        );
+        let submenu =
+            SubmenuMenuItem::new(String::from("Status"), vec![Box::new(value_item)]).unwrap();
+        let mut menu = Menu::new(vec![
+            Box::new(BasicMenuItem::new(String::from("label"))),
+            Box::new(submenu),
+        ])
+        .unwrap();
+
+        assert!(menu.update());
+        assert!(!menu.update());
+        temperature.set(23);
+        assert!(menu.update());
+        assert!(!menu.update());
+    }
+
+    #[test]
+    fn generation_changes_on_visible_changes() {
+        let temperature = Rc::new(Cell::new(21));
+        let temperature_clone = Rc::clone(&temperature);
+        let value_item: ValueMenuItem<i32> = ValueMenuItem::new(
+            String::from("Temp"),
+            Box::new(move || temperature_clone.get()),
+        );
+        let mut menu = Menu::new(vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(value_item),
+        ])
+        .unwrap();
+        menu.update();
+
+        let generation = menu.get_generation();
+        assert!(!menu.up());
+        assert!(!menu.update());
+        assert_eq!(menu.get_generation(), generation);
+
+        assert!(menu.down());
+        assert_ne!(menu.get_generation(), generation);
+
+        let generation = menu.get_generation();
+        temperature.set(23);
+        assert!(menu.update());
+        assert_ne!(menu.get_generation(), generation);
+
+        let generation = menu.get_generation();
+        menu.invalidate();
+        assert_ne!(menu.get_generation(), generation);
+    }
+
+    #[test]
+    fn clamped_picker_reports_boundaries() {
+        let mut list_item = ListMenuItem::new(
+            String::from("Zone"),
+            vec![String::from("Berlin"), String::from("London")],
+        )
+        .unwrap();
+        list_item.set_edit_mode(ListEditMode::Picker);
+        list_item.set_overflow_policy(OverflowPolicy::Clamp);
+        let mut menu = Menu::new(vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(list_item),
+            Box::new(BasicMenuItem::new(String::from("Item3"))),
+        ])
+        .unwrap();
+        assert!(menu.down());
+        assert!(menu.enter());
+
+        assert!(!menu.up());
+        assert!(menu.down());
+        assert!(!menu.down());
+        assert!(menu.get_submenu_menu_item().is_focused());
+        assert_eq!(menu.get_submenu_menu_item().get_selected_item_idx(), 1);
+
+        assert!(menu.enter());
+        assert_list_menu_item(
+            menu.get_submenu_menu_item()
+                .get_selected_item()
+                .as_any()
+                .downcast_ref::<ListMenuItem>()
+                .unwrap(),
+            false,
+            "Zone: London",
+            1,
+            "London",
+        );
+    }
+
+    #[test]
+    fn generation_changes_when_unhandled_press_drops_focus() {
+        let mut menu = Menu::new(vec![
+            Box::new(RangeMenuItem::new(String::from("Vol"), 0, 10, 1).unwrap()),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ])
+        .unwrap();
+        assert!(menu.enter());
+        assert!(menu.right());
+        assert_eq!(
+            menu.get_submenu_menu_item()
+                .get_selected_item()
+                .get_label(true),
+            "Vol: 1"
+        );
+
+        let generation = menu.get_generation();
+        assert!(!menu.up());
+        assert!(!menu.get_submenu_menu_item().is_focused());
+        assert_ne!(menu.get_generation(), generation);
+
+        let generation = menu.get_generation();
+        assert!(!menu.up());
+        assert_eq!(menu.get_generation(), generation);
+    }
 }
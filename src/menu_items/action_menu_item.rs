@@ -1,26 +1,41 @@
 use crate::keyboard::{FunctionKey, KeyboardKey};
 use crate::menu_items::menu_item::{MenuItem, PressResult};
+use core::fmt::Write;
 use std::any::Any;
 
 pub struct ActionMenuItem {
     label: String,
     on_pressed: Box<dyn FnMut() -> bool>,
+    enabled: bool,
 }
 
 impl ActionMenuItem {
     pub fn new(label: String, on_pressed: Box<dyn FnMut() -> bool>) -> ActionMenuItem {
-        ActionMenuItem { label, on_pressed }
+        ActionMenuItem {
+            label,
+            on_pressed,
+            enabled: true,
+        }
+    }
+
+    // A disabled action can be selected but not run
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
     }
 }
 
 impl MenuItem for ActionMenuItem {
-    fn get_label(&self, _is_focused: bool) -> String {
-        self.label.clone()
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        writer.write_str(&self.label)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
     }
 
     fn press(&mut self, key: &KeyboardKey, _is_focused: bool) -> PressResult {
         let handled = if let Some(function_key) = &key.function_key {
-            if *function_key == FunctionKey::ENTER {
+            if *function_key == FunctionKey::ENTER && self.enabled {
                 (self.on_pressed)();
                 true
             } else {
@@ -104,4 +119,25 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn disabled_item_is_not_run() {
+        let clicked_count = Rc::new(RefCell::new(0));
+        let clicked_count_clone = Rc::clone(&clicked_count);
+        let mut item: ActionMenuItem = ActionMenuItem::new(
+            String::from("label"),
+            Box::new(move || {
+                *clicked_count_clone.borrow_mut() += 1;
+                true
+            }),
+        );
+        item.set_enabled(false);
+        assert!(!item.is_enabled());
+        assert!(
+            !item
+                .press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false)
+                .handled
+        );
+        assert_eq!(*clicked_count.borrow(), 0);
+    }
 }
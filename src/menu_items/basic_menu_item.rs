@@ -1,5 +1,6 @@
 use crate::keyboard::KeyboardKey;
 use crate::menu_items::menu_item::{MenuItem, PressResult};
+use core::fmt::Write;
 use std::any::Any;
 
 pub struct BasicMenuItem {
@@ -13,8 +14,8 @@ impl BasicMenuItem {
 }
 
 impl MenuItem for BasicMenuItem {
-    fn get_label(&self, _is_focused: bool) -> String {
-        self.label.clone()
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        writer.write_str(&self.label)
     }
 
     fn press(&mut self, _key: &KeyboardKey, _is_focused: bool) -> PressResult {
@@ -0,0 +1,70 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+// Source of truth for an item value that lives outside of the menu.
+// Items read it when rendered or when an edit starts and write it back on commit.
+pub trait Binding<T> {
+    fn read(&self) -> T;
+    fn write(&mut self, value: T);
+}
+
+impl<T: Copy> Binding<T> for Rc<Cell<T>> {
+    fn read(&self) -> T {
+        Cell::get(self)
+    }
+
+    fn write(&mut self, value: T) {
+        Cell::set(self, value)
+    }
+}
+
+pub struct FnBinding<T> {
+    getter: Box<dyn Fn() -> T>,
+    setter: Box<dyn FnMut(T)>,
+}
+
+impl<T> FnBinding<T> {
+    pub fn new(getter: Box<dyn Fn() -> T>, setter: Box<dyn FnMut(T)>) -> FnBinding<T> {
+        FnBinding { getter, setter }
+    }
+}
+
+impl<T> Binding<T> for FnBinding<T> {
+    fn read(&self) -> T {
+        (self.getter)()
+    }
+
+    fn write(&mut self, value: T) {
+        (self.setter)(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    #[test]
+    fn cell_binding_works() {
+        let cell = Rc::new(Cell::new(5));
+        let mut binding = Rc::clone(&cell);
+        assert_eq!(binding.read(), 5);
+        binding.write(7);
+        assert_eq!(cell.get(), 7);
+    }
+
+    #[test]
+    fn fn_binding_works() {
+        let setting = Rc::new(RefCell::new(String::from("a")));
+        let getter_setting = Rc::clone(&setting);
+        let setter_setting = Rc::clone(&setting);
+        let mut binding = FnBinding::new(
+            Box::new(move || getter_setting.borrow().clone()),
+            Box::new(move |value| *setter_setting.borrow_mut() = value),
+        );
+        assert_eq!(binding.read(), "a");
+        binding.write(String::from("b"));
+        assert_eq!(*setting.borrow(), "b");
+    }
+}
@@ -0,0 +1,391 @@
+use crate::keyboard::{FunctionKey, KeyboardKey};
+use crate::menu_items::menu_item::{MenuItem, PressResult};
+use crate::renderer::display_width::WidthCounter;
+use core::fmt::Write;
+use std::any::Any;
+
+pub const MAX_ENTRY_COUNT: usize = 64;
+
+pub enum ChecklistSummary {
+    // "Days: Mon,Wed,Fri"
+    Entries,
+    // "Days: 3 selected"
+    Count,
+    // Entries if the label fits in the given display columns, count otherwise. Wide chars
+    // count as two columns, so the label fits with and without East Asian width. The item
+    // doesn't know the display, keep the value in sync with the renderer's label width, i.e.
+    // the display width minus the selection, closing bracket and scroll columns of the theme.
+    Fit(usize),
+}
+
+pub struct ChecklistMenuItem {
+    label: String,
+    entries: Vec<String>,
+    values: Vec<bool>,
+    focused_values: Vec<bool>,
+    // entries.len() is the confirm entry
+    focused_entry_idx: usize,
+    text_checked: String,
+    text_unchecked: String,
+    text_confirm: String,
+    summary: ChecklistSummary,
+}
+
+impl ChecklistMenuItem {
+    pub fn new(label: String, entries: Vec<String>) -> Result<ChecklistMenuItem, &'static str> {
+        if entries.is_empty() {
+            Err("At least one entry required")
+        } else if entries.len() > MAX_ENTRY_COUNT {
+            Err("Too many entries, see MAX_ENTRY_COUNT")
+        } else {
+            let entry_count = entries.len();
+            Ok(ChecklistMenuItem {
+                label,
+                entries,
+                values: vec![false; entry_count],
+                focused_values: vec![false; entry_count],
+                focused_entry_idx: 0,
+                text_checked: String::from("[x]"),
+                text_unchecked: String::from("[ ]"),
+                text_confirm: String::from("OK"),
+                summary: ChecklistSummary::Entries,
+            })
+        }
+    }
+
+    pub fn get_values(&self) -> &[bool] {
+        &self.values
+    }
+
+    pub fn set_values(&mut self, values: &[bool]) -> Result<(), &'static str> {
+        if values.len() != self.entries.len() {
+            Err("Values length must be equal to entries.len()")
+        } else {
+            self.values.copy_from_slice(values);
+            self.focused_values.copy_from_slice(values);
+            Ok(())
+        }
+    }
+
+    // Bit n is set if entry n is checked
+    pub fn get_bits(&self) -> u64 {
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value)
+            .fold(0, |bits, (entry_idx, _)| bits | (1 << entry_idx))
+    }
+
+    pub fn set_bits(&mut self, bits: u64) {
+        for (entry_idx, value) in self.values.iter_mut().enumerate() {
+            *value = bits & (1 << entry_idx) != 0;
+        }
+        self.focused_values.copy_from_slice(&self.values);
+    }
+
+    pub fn get_focused_entry_idx(&self) -> usize {
+        self.focused_entry_idx
+    }
+
+    pub fn set_text_checked(&mut self, text_checked: String) {
+        self.text_checked = text_checked;
+    }
+
+    pub fn set_text_unchecked(&mut self, text_unchecked: String) {
+        self.text_unchecked = text_unchecked;
+    }
+
+    pub fn set_text_confirm(&mut self, text_confirm: String) {
+        self.text_confirm = text_confirm;
+    }
+
+    pub fn set_summary(&mut self, summary: ChecklistSummary) {
+        self.summary = summary;
+    }
+
+    fn checked_entries(&self) -> impl Iterator<Item = &String> {
+        self.entries
+            .iter()
+            .zip(self.values.iter())
+            .filter(|(_, value)| **value)
+            .map(|(entry, _)| entry)
+    }
+
+    fn write_entries_summary(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        let mut is_first = true;
+        for entry in self.checked_entries() {
+            if !is_first {
+                writer.write_char(',')?;
+            }
+            writer.write_str(entry)?;
+            is_first = false;
+        }
+        if is_first {
+            writer.write_char('-')?;
+        }
+        Ok(())
+    }
+
+    fn write_count_summary(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        let selected_count = self.values.iter().filter(|value| **value).count();
+        write!(writer, "{} selected", selected_count)
+    }
+
+    fn enter(&mut self, is_focused: bool) -> PressResult {
+        if !is_focused {
+            self.focused_values.copy_from_slice(&self.values);
+            self.focused_entry_idx = 0;
+            PressResult {
+                handled: true,
+                focus: true,
+            }
+        } else if self.focused_entry_idx == self.entries.len() {
+            self.values.copy_from_slice(&self.focused_values);
+            PressResult {
+                handled: true,
+                focus: false,
+            }
+        } else {
+            let focused_value = &mut self.focused_values[self.focused_entry_idx];
+            *focused_value = !*focused_value;
+            PressResult {
+                handled: true,
+                focus: true,
+            }
+        }
+    }
+
+    fn back(&mut self) -> PressResult {
+        self.focused_values.copy_from_slice(&self.values);
+        PressResult {
+            handled: true,
+            focus: false,
+        }
+    }
+
+    fn left(&mut self) -> PressResult {
+        self.focused_entry_idx = if self.focused_entry_idx == 0 {
+            self.entries.len()
+        } else {
+            self.focused_entry_idx - 1
+        };
+        PressResult {
+            handled: true,
+            focus: true,
+        }
+    }
+
+    fn right(&mut self) -> PressResult {
+        self.focused_entry_idx = if self.focused_entry_idx == self.entries.len() {
+            0
+        } else {
+            self.focused_entry_idx + 1
+        };
+        PressResult {
+            handled: true,
+            focus: true,
+        }
+    }
+}
+
+impl MenuItem for ChecklistMenuItem {
+    fn write_label(&self, writer: &mut dyn Write, is_focused: bool) -> core::fmt::Result {
+        write!(writer, "{}: ", self.label)?;
+        if is_focused {
+            if self.focused_entry_idx == self.entries.len() {
+                writer.write_str(&self.text_confirm)
+            } else {
+                let check_text = if self.focused_values[self.focused_entry_idx] {
+                    &self.text_checked
+                } else {
+                    &self.text_unchecked
+                };
+                write!(
+                    writer,
+                    "{} {}",
+                    check_text, self.entries[self.focused_entry_idx]
+                )
+            }
+        } else {
+            match self.summary {
+                ChecklistSummary::Entries => self.write_entries_summary(writer),
+                ChecklistSummary::Count => self.write_count_summary(writer),
+                ChecklistSummary::Fit(max_width) => {
+                    let mut width_counter = WidthCounter::new(true);
+                    write!(width_counter, "{}: ", self.label)?;
+                    self.write_entries_summary(&mut width_counter)?;
+                    if width_counter.width > max_width {
+                        self.write_count_summary(writer)
+                    } else {
+                        self.write_entries_summary(writer)
+                    }
+                }
+            }
+        }
+    }
+
+    fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult {
+        match &key.function_key {
+            Some(FunctionKey::ENTER) => self.enter(is_focused),
+            Some(FunctionKey::BACK) if is_focused => self.back(),
+            Some(FunctionKey::LEFT) if is_focused => self.left(),
+            Some(FunctionKey::RIGHT) if is_focused => self.right(),
+            _ => PressResult {
+                handled: false,
+                focus: is_focused,
+            },
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_days_item() -> ChecklistMenuItem {
+        let entries = vec![
+            String::from("Mon"),
+            String::from("Tue"),
+            String::from("Wed"),
+            String::from("Thu"),
+            String::from("Fri"),
+        ];
+        ChecklistMenuItem::new(String::from("Days"), entries).unwrap()
+    }
+
+    fn press_key(item: &mut ChecklistMenuItem, function_key: FunctionKey, is_focused: bool) {
+        item.press(&KeyboardKey::new(Some(function_key), None), is_focused);
+    }
+
+    #[test]
+    fn new_fails_invalid_entries() {
+        let item_result = ChecklistMenuItem::new(String::from("label"), Vec::new());
+        assert_eq!(item_result.err(), Some("At least one entry required"));
+
+        let entries = vec![String::from("entry"); 65];
+        let item_result = ChecklistMenuItem::new(String::from("label"), entries);
+        assert_eq!(
+            item_result.err(),
+            Some("Too many entries, see MAX_ENTRY_COUNT")
+        );
+    }
+
+    #[test]
+    fn item_is_usable() {
+        let mut item = create_days_item();
+        assert_eq!(item.get_label(false), "Days: -");
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(true), "Days: [ ] Mon");
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(true), "Days: [x] Mon");
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_label(true), "Days: [x] Wed");
+        for _ in 0..4 {
+            press_key(&mut item, FunctionKey::LEFT, true);
+        }
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_label(true), "Days: [x] Fri");
+        assert_eq!(item.get_label(false), "Days: -");
+
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        assert_eq!(item.get_label(true), "Days: OK");
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(false), "Days: Mon,Wed,Fri");
+        assert_eq!(item.get_values(), &[true, false, true, false, true]);
+        assert_eq!(item.get_bits(), 0b10101);
+    }
+
+    #[test]
+    fn back_cancels_changes() {
+        let mut item = create_days_item();
+        item.set_bits(0b00010);
+        assert_eq!(item.get_label(false), "Days: Tue");
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(false), "Days: Tue");
+        assert_eq!(item.get_values(), &[false, true, false, false, false]);
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        assert_eq!(item.get_label(true), "Days: [ ] Mon");
+    }
+
+    #[test]
+    fn summary_fits_max_width() {
+        let mut item = create_days_item();
+        item.set_values(&[true, true, false, true, true]).unwrap();
+        assert_eq!(item.get_label(false), "Days: Mon,Tue,Thu,Fri");
+
+        item.set_summary(ChecklistSummary::Count);
+        assert_eq!(item.get_label(false), "Days: 4 selected");
+
+        item.set_summary(ChecklistSummary::Fit(14));
+        assert_eq!(item.get_label(false), "Days: 4 selected");
+        item.set_values(&[true, false, false, false, true]).unwrap();
+        assert_eq!(item.get_label(false), "Days: Mon,Fri");
+
+        let entries = vec![String::from("月"), String::from("火"), String::from("水")];
+        let mut item = ChecklistMenuItem::new(String::from("曜日"), entries).unwrap();
+        item.set_summary(ChecklistSummary::Fit(11));
+        item.set_values(&[true, true, false]).unwrap();
+        assert_eq!(item.get_label(false), "曜日: 月,火");
+        item.set_values(&[true, true, true]).unwrap();
+        assert_eq!(item.get_label(false), "曜日: 3 selected");
+
+        assert_eq!(
+            item.set_values(&[true]),
+            Err("Values length must be equal to entries.len()")
+        );
+    }
+
+    #[test]
+    fn texts_are_configurable() {
+        let mut item = create_days_item();
+        item.set_text_checked(String::from("*"));
+        item.set_text_unchecked(String::from("-"));
+        item.set_text_confirm(String::from("Save"));
+        press_key(&mut item, FunctionKey::ENTER, false);
+        assert_eq!(item.get_label(true), "Days: - Mon");
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_label(true), "Days: * Mon");
+        press_key(&mut item, FunctionKey::LEFT, true);
+        assert_eq!(item.get_label(true), "Days: Save");
+    }
+}
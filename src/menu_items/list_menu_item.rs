@@ -1,101 +1,343 @@
 use crate::keyboard::{FunctionKey, KeyboardKey};
-use crate::menu_items::menu_item::{MenuItem, PressResult};
-use core::fmt::Write;
+use crate::menu_items::binding::Binding;
+use crate::menu_items::menu_item::{MenuItem, PickerView, PressResult};
+use crate::menu_items::overflow_policy::{Boundary, OverflowPolicy};
+use core::fmt::{Display, Write};
 use std::any::Any;
 
-pub struct ListMenuItem {
+// Enums selectable with ListMenuItem::from_enum, labels come from Display
+pub trait MenuEnum: Sized {
+    fn variants() -> Vec<Self>;
+}
+
+// Entries produced on demand, e.g. scan results or stored records
+pub trait ListSource<T> {
+    fn count(&self) -> usize;
+    fn get(&self, idx: usize) -> T;
+    // Can be implemented to render without allocating
+    fn write_entry(&self, writer: &mut dyn Write, idx: usize) -> core::fmt::Result
+    where
+        T: Display,
+    {
+        write!(writer, "{}", self.get(idx))
+    }
+    // Called every time the item is entered
+    fn refresh(&mut self) {}
+}
+
+impl<T: Clone> ListSource<T> for Vec<T> {
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, idx: usize) -> T {
+        self[idx].clone()
+    }
+
+    fn write_entry(&self, writer: &mut dyn Write, idx: usize) -> core::fmt::Result
+    where
+        T: Display,
+    {
+        write!(writer, "{}", self[idx])
+    }
+}
+
+pub enum ListEditMode {
+    // LEFT/RIGHT cycle through the entries on the item line
+    Inline,
+    // Entries are shown full screen, UP/DOWN move and typing a char jumps to the first
+    // entry starting with it
+    Picker,
+}
+
+pub struct ListMenuItem<T: Display + Clone + 'static = String> {
     label: String,
-    entries: Vec<String>,
+    entries: Box<dyn ListSource<T>>,
     selected_entry_idx: usize,
     focus_selected_entry_idx: usize,
+    overflow_policy: OverflowPolicy,
+    edit_mode: ListEditMode,
+    // Hooks
+    on_preview: Option<Box<dyn FnMut(usize)>>,
+    on_revert: Option<Box<dyn FnMut(usize)>>,
+    on_commit: Option<Box<dyn FnMut(usize)>>,
+    binding: Option<Box<dyn Binding<usize>>>,
 }
 
-impl ListMenuItem {
-    pub fn new(label: String, entries: Vec<String>) -> Result<ListMenuItem, &'static str> {
+impl<T: Display + Clone + 'static> ListMenuItem<T> {
+    pub fn new(label: String, entries: Vec<T>) -> Result<ListMenuItem<T>, &'static str> {
         if entries.is_empty() {
             Err("At least one entry required")
         } else {
-            let menu_item = ListMenuItem {
-                label,
-                entries,
-                selected_entry_idx: 0,
-                focus_selected_entry_idx: 0,
-            };
-            Ok(menu_item)
+            Ok(ListMenuItem::new_with_source(label, Box::new(entries)))
+        }
+    }
+
+    // An empty source is displayed as "-" and can't be entered
+    pub fn new_with_source(label: String, entries: Box<dyn ListSource<T>>) -> ListMenuItem<T> {
+        ListMenuItem {
+            label,
+            entries,
+            selected_entry_idx: 0,
+            focus_selected_entry_idx: 0,
+            overflow_policy: OverflowPolicy::Wrap,
+            edit_mode: ListEditMode::Inline,
+            on_preview: None,
+            on_revert: None,
+            on_commit: None,
+            binding: None,
         }
     }
 
+    // Bound entry idxs outside of the entries are clamped to the last entry, the clamped idx
+    // is written back when the item loads it, e.g. on enter
     pub fn get_selected_entry_idx(&self) -> usize {
-        self.selected_entry_idx
+        match &self.binding {
+            Some(binding) => self.clamp_entry_idx(binding.read()),
+            None => self.selected_entry_idx,
+        }
+    }
+
+    fn clamp_entry_idx(&self, entry_idx: usize) -> usize {
+        entry_idx.min(self.entries.count().saturating_sub(1))
     }
 
     pub fn set_selected_entry_idx(
         &mut self,
         selected_entry_idx: usize,
     ) -> Result<(), &'static str> {
-        if selected_entry_idx >= self.entries.len() {
+        if selected_entry_idx >= self.entries.count() {
             Err("Selected entry idx must be between 0 and entries.len()")
         } else {
             self.selected_entry_idx = selected_entry_idx;
             self.focus_selected_entry_idx = selected_entry_idx;
+            self.store_entry_idx();
             Ok(())
         }
     }
 
-    pub fn select_next_entry(&mut self) {
-        self.selected_entry_idx = if self.selected_entry_idx == self.entries.len() - 1 {
-            0
-        } else {
-            self.selected_entry_idx + 1
-        };
+    pub fn set_binding(&mut self, binding: Box<dyn Binding<usize>>) {
+        self.binding = Some(binding);
+        self.load_entry_idx();
+    }
+
+    fn load_entry_idx(&mut self) {
+        if let Some(binding) = &self.binding {
+            let bound_entry_idx = binding.read();
+            self.selected_entry_idx = self.clamp_entry_idx(bound_entry_idx);
+            if self.selected_entry_idx != bound_entry_idx {
+                self.store_entry_idx();
+            }
+        }
         self.focus_selected_entry_idx = self.selected_entry_idx;
     }
 
-    pub fn select_prev_entry(&mut self) {
-        self.selected_entry_idx = if self.selected_entry_idx == 0 {
-            self.entries.len() - 1
+    fn store_entry_idx(&mut self) {
+        if let Some(binding) = &mut self.binding {
+            binding.write(self.selected_entry_idx);
+        }
+    }
+
+    pub fn set_overflow_policy(&mut self, overflow_policy: OverflowPolicy) {
+        self.overflow_policy = overflow_policy;
+    }
+
+    pub fn set_edit_mode(&mut self, edit_mode: ListEditMode) {
+        self.edit_mode = edit_mode;
+    }
+
+    // Called with the focused entry idx every time it changes while editing
+    pub fn set_on_preview(&mut self, on_preview: Box<dyn FnMut(usize)>) {
+        self.on_preview = Some(on_preview);
+    }
+
+    // Called with the restored entry idx when BACK discards the focused entry
+    pub fn set_on_revert(&mut self, on_revert: Box<dyn FnMut(usize)>) {
+        self.on_revert = Some(on_revert);
+    }
+
+    // Called with the new entry idx when ENTER confirms the focused entry
+    pub fn set_on_commit(&mut self, on_commit: Box<dyn FnMut(usize)>) {
+        self.on_commit = Some(on_commit);
+    }
+
+    fn preview(&mut self) {
+        if let Some(on_preview) = &mut self.on_preview {
+            on_preview(self.focus_selected_entry_idx);
+        }
+    }
+
+    fn next_entry_idx(&mut self, entry_idx: usize) -> Option<usize> {
+        if self.entries.count() == 0 {
+            None
+        } else if entry_idx + 1 >= self.entries.count() {
+            self.overflow_policy.overflow(Boundary::Max, 0)
         } else {
-            self.selected_entry_idx - 1
-        };
-        self.focus_selected_entry_idx = self.selected_entry_idx;
+            Some(entry_idx + 1)
+        }
     }
 
-    pub fn select_focused_next_entry(&mut self) {
-        self.focus_selected_entry_idx = if self.focus_selected_entry_idx == self.entries.len() - 1 {
-            0
+    fn prev_entry_idx(&mut self, entry_idx: usize) -> Option<usize> {
+        if self.entries.count() == 0 {
+            None
+        } else if entry_idx == 0 {
+            let last_entry_idx = self.entries.count() - 1;
+            self.overflow_policy.overflow(Boundary::Min, last_entry_idx)
         } else {
-            self.focus_selected_entry_idx + 1
-        };
+            Some(entry_idx - 1)
+        }
+    }
+
+    pub fn select_next_entry(&mut self) -> bool {
+        self.load_entry_idx();
+        if let Some(new_entry_idx) = self.next_entry_idx(self.selected_entry_idx) {
+            self.selected_entry_idx = new_entry_idx;
+            self.focus_selected_entry_idx = new_entry_idx;
+            self.store_entry_idx();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_prev_entry(&mut self) -> bool {
+        self.load_entry_idx();
+        if let Some(new_entry_idx) = self.prev_entry_idx(self.selected_entry_idx) {
+            self.selected_entry_idx = new_entry_idx;
+            self.focus_selected_entry_idx = new_entry_idx;
+            self.store_entry_idx();
+            true
+        } else {
+            false
+        }
     }
 
-    pub fn select_focused_prev_entry(&mut self) {
-        self.focus_selected_entry_idx = if self.focus_selected_entry_idx == 0 {
-            self.entries.len() - 1
+    pub fn select_focused_next_entry(&mut self) -> bool {
+        if let Some(new_entry_idx) = self.next_entry_idx(self.focus_selected_entry_idx) {
+            self.focus_selected_entry_idx = new_entry_idx;
+            true
         } else {
-            self.focus_selected_entry_idx - 1
+            false
+        }
+    }
+
+    pub fn select_focused_prev_entry(&mut self) -> bool {
+        if let Some(new_entry_idx) = self.prev_entry_idx(self.focus_selected_entry_idx) {
+            self.focus_selected_entry_idx = new_entry_idx;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn jump_to_char(&mut self, char_input: &[u8]) -> bool {
+        let Some(jump_char) = core::str::from_utf8(char_input)
+            .ok()
+            .and_then(|char_str| char_str.chars().next())
+        else {
+            return false;
         };
+        let jump_entry_idx = (0..self.entries.count()).find(|entry_idx| {
+            self.entries
+                .get(*entry_idx)
+                .to_string()
+                .chars()
+                .next()
+                .is_some_and(|first_char| first_char.to_lowercase().eq(jump_char.to_lowercase()))
+        });
+        match jump_entry_idx {
+            Some(entry_idx) if entry_idx != self.focus_selected_entry_idx => {
+                self.focus_selected_entry_idx = entry_idx;
+                self.preview();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries.count()
+    }
+
+    // None while the source is empty
+    pub fn get_selected_entry(&self) -> Option<T> {
+        self.get_entry(self.get_selected_entry_idx())
+    }
+
+    pub fn get_focused_selected_entry(&self) -> Option<T> {
+        self.get_entry(self.focus_selected_entry_idx)
+    }
+
+    fn get_entry(&self, entry_idx: usize) -> Option<T> {
+        if entry_idx < self.entries.count() {
+            Some(self.entries.get(entry_idx))
+        } else {
+            None
+        }
+    }
+
+    // Refreshes the source and keeps the selection within the new entries
+    fn refresh(&mut self) {
+        self.entries.refresh();
+        let last_entry_idx = self.entries.count().saturating_sub(1);
+        self.selected_entry_idx = self.selected_entry_idx.min(last_entry_idx);
+        self.load_entry_idx();
+    }
+}
+
+impl<T: Display + Clone + PartialEq + 'static> ListMenuItem<T> {
+    pub fn set_selected_entry(&mut self, entry: &T) -> Result<(), &'static str> {
+        match (0..self.entries.count()).find(|entry_idx| self.entries.get(*entry_idx) == *entry) {
+            Some(entry_idx) => self.set_selected_entry_idx(entry_idx),
+            None => Err("Entry must be one of the entries"),
+        }
+    }
+}
+
+impl<T: MenuEnum + Display + Clone + 'static> ListMenuItem<T> {
+    pub fn from_enum(label: String) -> Result<ListMenuItem<T>, &'static str> {
+        ListMenuItem::new(label, T::variants())
+    }
+}
+
+impl<T: Display + Clone + 'static> PickerView for ListMenuItem<T> {
+    fn entry_count(&self) -> usize {
+        self.entries.count()
     }
 
-    pub fn get_selected_entry(&self) -> &str {
-        &self.entries[self.selected_entry_idx]
+    fn write_entry_label(&self, writer: &mut dyn Write, entry_idx: usize) -> core::fmt::Result {
+        self.entries.write_entry(writer, entry_idx)
     }
 
-    pub fn get_focused_selected_entry(&self) -> &str {
-        &self.entries[self.focus_selected_entry_idx]
+    fn get_focused_entry_idx(&self) -> usize {
+        self.focus_selected_entry_idx
     }
 }
 
-impl MenuItem for ListMenuItem {
-    fn get_label(&self, is_focused: bool) -> String {
-        let selected_entry = if is_focused {
-            self.get_focused_selected_entry()
+impl<T: Display + Clone + 'static> MenuItem for ListMenuItem<T> {
+    fn write_label(&self, writer: &mut dyn Write, is_focused: bool) -> core::fmt::Result {
+        write!(writer, "{}: ", &self.label)?;
+        let entry_idx = if is_focused {
+            self.focus_selected_entry_idx
         } else {
-            self.get_selected_entry()
+            self.get_selected_entry_idx()
         };
+        if entry_idx < self.entries.count() {
+            self.entries.write_entry(writer, entry_idx)
+        } else {
+            writer.write_char('-')
+        }
+    }
+
+    fn write_name(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        writer.write_str(&self.label)
+    }
 
-        let mut label_str: String = String::new();
-        write!(label_str, "{}: {}", &self.label, selected_entry).unwrap();
-        label_str
+    fn get_picker_view(&self) -> Option<&dyn PickerView> {
+        match self.edit_mode {
+            ListEditMode::Inline => None,
+            ListEditMode::Picker => Some(self),
+        }
     }
 
     fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult {
@@ -106,35 +348,59 @@ impl MenuItem for ListMenuItem {
                 FunctionKey::ENTER => {
                     if is_focused {
                         self.selected_entry_idx = self.focus_selected_entry_idx;
+                        self.store_entry_idx();
+                        if let Some(on_commit) = &mut self.on_commit {
+                            on_commit(self.selected_entry_idx);
+                        }
+                        focus = false;
                     } else {
-                        self.focus_selected_entry_idx = self.selected_entry_idx;
+                        self.refresh();
+                        focus = self.entries.count() != 0;
                     }
-                    focus = !is_focused;
                     handled = true;
                 }
-                FunctionKey::BACK => {
-                    if is_focused {
-                        self.focus_selected_entry_idx = self.selected_entry_idx;
-                        handled = true;
-                        focus = false;
+                FunctionKey::BACK if is_focused => {
+                    self.focus_selected_entry_idx = self.selected_entry_idx;
+                    if let Some(on_revert) = &mut self.on_revert {
+                        on_revert(self.selected_entry_idx);
                     }
+                    handled = true;
+                    focus = false;
                 }
-                FunctionKey::LEFT => {
-                    if is_focused {
-                        self.select_focused_prev_entry();
-                        handled = true;
-                        focus = true;
+                FunctionKey::LEFT if is_focused => {
+                    handled = self.select_focused_prev_entry();
+                    if handled {
+                        self.preview();
                     }
+                    focus = true;
                 }
-                FunctionKey::RIGHT => {
-                    if is_focused {
-                        self.select_focused_next_entry();
-                        handled = true;
-                        focus = true;
+                FunctionKey::RIGHT if is_focused => {
+                    handled = self.select_focused_next_entry();
+                    if handled {
+                        self.preview();
+                    }
+                    focus = true;
+                }
+                FunctionKey::UP if is_focused && matches!(self.edit_mode, ListEditMode::Picker) => {
+                    handled = self.select_focused_prev_entry();
+                    if handled {
+                        self.preview();
+                    }
+                }
+                FunctionKey::DOWN
+                    if is_focused && matches!(self.edit_mode, ListEditMode::Picker) =>
+                {
+                    handled = self.select_focused_next_entry();
+                    if handled {
+                        self.preview();
                     }
                 }
                 _ => {}
             };
+        } else if let Some(char_input) = &key.char_input {
+            if is_focused && matches!(self.edit_mode, ListEditMode::Picker) {
+                handled = self.jump_to_char(char_input);
+            }
         }
 
         PressResult { handled, focus }
@@ -152,6 +418,10 @@ impl MenuItem for ListMenuItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::menu_items::binding::FnBinding;
+
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
 
     #[test]
     fn select_next_entry_works() {
@@ -324,4 +594,280 @@ mod tests {
         assert_eq!(item.get_label(true), "label: Elem2");
         assert_eq!(item.get_selected_entry_idx(), 1);
     }
+
+    #[test]
+    fn clamp_stops_at_boundaries() {
+        let list_entries = vec![String::from("Elem1"), String::from("Elem2")];
+        let mut item: ListMenuItem =
+            ListMenuItem::new(String::from("label"), list_entries).unwrap();
+        item.set_overflow_policy(OverflowPolicy::Clamp);
+
+        assert!(!item.select_prev_entry());
+        assert_eq!(item.get_selected_entry_idx(), 0);
+        assert!(item.select_next_entry());
+        assert!(!item.select_next_entry());
+        assert_eq!(item.get_selected_entry_idx(), 1);
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::RIGHT), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_label(true), "label: Elem2");
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(true), "label: Elem1");
+    }
+
+    #[test]
+    fn hooks_are_called() {
+        let list_entries = vec![
+            String::from("Low"),
+            String::from("Mid"),
+            String::from("High"),
+        ];
+        let mut item: ListMenuItem =
+            ListMenuItem::new(String::from("Volume"), list_entries).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let preview_events = Rc::clone(&events);
+        item.set_on_preview(Box::new(move |entry_idx| {
+            preview_events.borrow_mut().push(("preview", entry_idx))
+        }));
+        let revert_events = Rc::clone(&events);
+        item.set_on_revert(Box::new(move |entry_idx| {
+            revert_events.borrow_mut().push(("revert", entry_idx))
+        }));
+        let commit_events = Rc::clone(&events);
+        item.set_on_commit(Box::new(move |entry_idx| {
+            commit_events.borrow_mut().push(("commit", entry_idx))
+        }));
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::RIGHT), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true);
+        assert_eq!(*events.borrow(), vec![("preview", 1), ("revert", 0)]);
+
+        events.borrow_mut().clear();
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true);
+        assert_eq!(*events.borrow(), vec![("preview", 2), ("commit", 2)]);
+        assert_eq!(item.get_selected_entry().as_deref(), Some("High"));
+    }
+
+    #[test]
+    fn bound_entry_idx_is_read_and_written() {
+        let list_entries = vec![
+            String::from("Low"),
+            String::from("Mid"),
+            String::from("High"),
+        ];
+        let mut item: ListMenuItem =
+            ListMenuItem::new(String::from("Volume"), list_entries).unwrap();
+        let volume = Rc::new(Cell::new(1));
+        let getter_volume = Rc::clone(&volume);
+        let setter_volume = Rc::clone(&volume);
+        item.set_binding(Box::new(FnBinding::new(
+            Box::new(move || getter_volume.get()),
+            Box::new(move |entry_idx| setter_volume.set(entry_idx)),
+        )));
+        assert_eq!(item.get_label(false), "Volume: Mid");
+
+        volume.set(2);
+        assert_eq!(item.get_label(false), "Volume: High");
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true);
+        assert_eq!(volume.get(), 2);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true);
+        assert_eq!(volume.get(), 1);
+
+        volume.set(3);
+        assert_eq!(item.get_label(false), "Volume: High");
+        assert_eq!(volume.get(), 3);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(volume.get(), 2);
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum FanMode {
+        Off,
+        Auto,
+        Max,
+    }
+
+    impl Display for FanMode {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let text = match self {
+                FanMode::Off => "Off",
+                FanMode::Auto => "Auto",
+                FanMode::Max => "Max",
+            };
+            f.write_str(text)
+        }
+    }
+
+    impl MenuEnum for FanMode {
+        fn variants() -> Vec<Self> {
+            vec![FanMode::Off, FanMode::Auto, FanMode::Max]
+        }
+    }
+
+    #[test]
+    fn enum_item_works() {
+        let mut item: ListMenuItem<FanMode> = ListMenuItem::from_enum(String::from("Fan")).unwrap();
+        assert_eq!(item.get_label(false), "Fan: Off");
+        assert_eq!(item.get_selected_entry(), Some(FanMode::Off));
+
+        item.set_selected_entry(&FanMode::Max).unwrap();
+        assert_eq!(item.get_label(false), "Fan: Max");
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::RIGHT), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true);
+        assert_eq!(item.get_selected_entry(), Some(FanMode::Off));
+    }
+
+    #[test]
+    fn set_selected_entry_fails_unknown_entry() {
+        let mut item: ListMenuItem<u32> =
+            ListMenuItem::new(String::from("Channel"), vec![1, 6, 11]).unwrap();
+        item.set_selected_entry(&6).unwrap();
+        assert_eq!(item.get_label(false), "Channel: 6");
+        assert_eq!(
+            item.set_selected_entry(&7),
+            Err("Entry must be one of the entries")
+        );
+        assert_eq!(item.get_selected_entry_idx(), 1);
+    }
+
+    #[test]
+    fn picker_mode_is_usable() {
+        let list_entries = vec![
+            String::from("Berlin"),
+            String::from("London"),
+            String::from("Madrid"),
+            String::from("Moscow"),
+        ];
+        let mut item: ListMenuItem = ListMenuItem::new(String::from("Zone"), list_entries).unwrap();
+        assert!(item.get_picker_view().is_none());
+        item.set_edit_mode(ListEditMode::Picker);
+        let picker_view = item.get_picker_view().unwrap();
+        assert_eq!(picker_view.entry_count(), 4);
+        assert_eq!(picker_view.get_entry_label(1), "London");
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_focused_entry_idx(), 1);
+        assert_eq!(
+            item.press(&KeyboardKey::new(None, Some(b"m".to_vec())), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_focused_entry_idx(), 2);
+        assert_eq!(
+            item.press(&KeyboardKey::new(None, Some(b"x".to_vec())), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true);
+        assert_eq!(item.get_focused_entry_idx(), 3);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_selected_entry().as_deref(), Some("Berlin"));
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(None, Some(b"L".to_vec())), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true);
+        assert_eq!(item.get_selected_entry().as_deref(), Some("London"));
+    }
+
+    struct ScanSource {
+        scan_results: Rc<RefCell<Vec<String>>>,
+        entries: Vec<String>,
+    }
+
+    impl ListSource<String> for ScanSource {
+        fn count(&self) -> usize {
+            self.entries.len()
+        }
+
+        fn get(&self, idx: usize) -> String {
+            self.entries[idx].clone()
+        }
+
+        fn refresh(&mut self) {
+            self.entries = self.scan_results.borrow().clone();
+        }
+    }
+
+    #[test]
+    fn source_is_refreshed_on_enter() {
+        let scan_results = Rc::new(RefCell::new(Vec::new()));
+        let source = ScanSource {
+            scan_results: Rc::clone(&scan_results),
+            entries: Vec::new(),
+        };
+        let mut item: ListMenuItem =
+            ListMenuItem::new_with_source(String::from("Network"), Box::new(source));
+        assert_eq!(item.get_label(false), "Network: -");
+        assert_eq!(item.get_selected_entry(), None);
+        assert_eq!(item.get_focused_selected_entry(), None);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+
+        scan_results
+            .borrow_mut()
+            .extend([String::from("home"), String::from("office")]);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.entry_count(), 2);
+        item.press(&KeyboardKey::new(Some(FunctionKey::RIGHT), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true);
+        assert_eq!(item.get_label(false), "Network: office");
+        assert_eq!(item.get_selected_entry().as_deref(), Some("office"));
+
+        scan_results.borrow_mut().truncate(1);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(item.get_label(true), "Network: home");
+    }
 }
@@ -1,4 +1,6 @@
 use crate::keyboard::KeyboardKey;
+use crate::menu_items::submenu_menu_item::SubmenuMenuItem;
+use core::fmt::Write;
 use std::any::Any;
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
@@ -7,9 +9,62 @@ pub struct PressResult {
     pub focus: bool,
 }
 
+// Lets renderer themes draw items differently, e.g. toggles as checkboxes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemKind {
+    Other,
+    // Checked state of the displayed value
+    Toggle(bool),
+    // Opens a list of entries when entered
+    Submenu,
+}
+
+// Entries displayed full screen by the renderer while the item is focused
+pub trait PickerView {
+    fn entry_count(&self) -> usize;
+    fn write_entry_label(&self, writer: &mut dyn Write, entry_idx: usize) -> core::fmt::Result;
+    fn get_entry_label(&self, entry_idx: usize) -> String {
+        let mut label_str: String = String::new();
+        self.write_entry_label(&mut label_str, entry_idx).unwrap();
+        label_str
+    }
+    fn get_focused_entry_idx(&self) -> usize;
+}
+
 pub trait MenuItem: Any {
-    fn get_label(&self, is_focused: bool) -> String;
+    // Writes the label without allocating where possible, e.g. into a frame buffer
+    fn write_label(&self, writer: &mut dyn Write, is_focused: bool) -> core::fmt::Result;
+    fn get_label(&self, is_focused: bool) -> String {
+        let mut label_str: String = String::new();
+        self.write_label(&mut label_str, is_focused).unwrap();
+        label_str
+    }
+    // Label without the value, for themes displaying the value themselves
+    fn write_name(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        self.write_label(writer, false)
+    }
+    fn get_kind(&self, _is_focused: bool) -> ItemKind {
+        ItemKind::Other
+    }
+    fn is_enabled(&self) -> bool {
+        true
+    }
+    // Char idx in the label of the position being edited, if any. Not marked in the label.
+    fn get_cursor_position(&self, _is_focused: bool) -> Option<usize> {
+        None
+    }
+    fn get_picker_view(&self) -> Option<&dyn PickerView> {
+        None
+    }
+    // Submenu whose items are displayed full screen while the item is focused
+    fn get_child_submenu(&self) -> Option<&SubmenuMenuItem> {
+        None
+    }
     fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult;
+    // Refreshes live values, returns true if the label changed and a redraw is needed
+    fn update(&mut self) -> bool {
+        false
+    }
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -4,14 +4,36 @@ pub mod action_menu_item;
 #[cfg(feature = "basic_menu_item")]
 pub mod basic_menu_item;
 
+#[cfg(any(
+    feature = "list_menu_item",
+    feature = "range_menu_item",
+    feature = "toggle_menu_item"
+))]
+pub mod binding;
+
+#[cfg(feature = "checklist_menu_item")]
+pub mod checklist_menu_item;
+
 #[cfg(feature = "list_menu_item")]
 pub mod list_menu_item;
 pub mod menu_item;
 
+#[cfg(any(feature = "list_menu_item", feature = "range_menu_item"))]
+pub mod overflow_policy;
+
+#[cfg(feature = "radio_menu_item")]
+pub mod radio_menu_item;
+
 #[cfg(feature = "range_menu_item")]
 pub mod range_menu_item;
 
+#[cfg(feature = "range_menu_item")]
+pub mod range_value;
+
 pub mod submenu_menu_item;
 
 #[cfg(feature = "toggle_menu_item")]
 pub mod toggle_menu_item;
+
+#[cfg(feature = "value_menu_item")]
+pub mod value_menu_item;
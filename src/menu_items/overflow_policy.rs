@@ -0,0 +1,49 @@
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum Boundary {
+    Min,
+    Max,
+}
+
+pub enum OverflowPolicy {
+    Wrap,
+    Clamp,
+    ClampWithEvent(Box<dyn FnMut(Boundary)>),
+}
+
+impl OverflowPolicy {
+    // Returns the value to continue with after stepping past the boundary,
+    // or None if the value must stay where it is
+    pub(crate) fn overflow<T>(&mut self, boundary: Boundary, wrapped_value: T) -> Option<T> {
+        match self {
+            OverflowPolicy::Wrap => Some(wrapped_value),
+            OverflowPolicy::Clamp => None,
+            OverflowPolicy::ClampWithEvent(on_boundary) => {
+                on_boundary(boundary);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn overflow_follows_policy() {
+        assert_eq!(OverflowPolicy::Wrap.overflow(Boundary::Max, 0), Some(0));
+        assert_eq!(OverflowPolicy::Clamp.overflow(Boundary::Max, 0), None);
+
+        let boundaries = Rc::new(RefCell::new(Vec::new()));
+        let boundaries_clone = Rc::clone(&boundaries);
+        let mut policy = OverflowPolicy::ClampWithEvent(Box::new(move |boundary| {
+            boundaries_clone.borrow_mut().push(boundary)
+        }));
+        assert_eq!(policy.overflow(Boundary::Max, 0), None);
+        assert_eq!(policy.overflow(Boundary::Min, 10), None);
+        assert_eq!(*boundaries.borrow(), vec![Boundary::Max, Boundary::Min]);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::keyboard::{FunctionKey, KeyboardKey};
+use crate::menu_items::menu_item::{ItemKind, MenuItem, PressResult};
+use crate::menu_items::submenu_menu_item::SubmenuMenuItem;
+use core::fmt::Write;
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct RadioState {
+    entries: Vec<String>,
+    selected_entry_idx: usize,
+    text_selected: String,
+    text_unselected: String,
+}
+
+struct RadioOptionMenuItem {
+    entry_idx: usize,
+    state: Rc<RefCell<RadioState>>,
+}
+
+impl MenuItem for RadioOptionMenuItem {
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        let state = self.state.borrow();
+        let marker = if state.selected_entry_idx == self.entry_idx {
+            &state.text_selected
+        } else {
+            &state.text_unselected
+        };
+        write!(writer, "{} {}", marker, state.entries[self.entry_idx])
+    }
+
+    fn press(&mut self, _key: &KeyboardKey, _is_focused: bool) -> PressResult {
+        PressResult {
+            handled: false,
+            focus: false,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// Shows every entry on its own line. ENTER on an entry selects it and goes back to the parent.
+pub struct RadioMenuItem {
+    label: String,
+    submenu: SubmenuMenuItem,
+    state: Rc<RefCell<RadioState>>,
+}
+
+impl RadioMenuItem {
+    pub fn new(label: String, entries: Vec<String>) -> Result<RadioMenuItem, &'static str> {
+        if entries.is_empty() {
+            return Err("At least one entry required");
+        }
+
+        let entry_count = entries.len();
+        let state = Rc::new(RefCell::new(RadioState {
+            entries,
+            selected_entry_idx: 0,
+            text_selected: String::from("(*)"),
+            text_unselected: String::from("( )"),
+        }));
+        let items: Vec<Box<dyn MenuItem>> = (0..entry_count)
+            .map(|entry_idx| {
+                Box::new(RadioOptionMenuItem {
+                    entry_idx,
+                    state: Rc::clone(&state),
+                }) as Box<dyn MenuItem>
+            })
+            .collect();
+        let submenu = SubmenuMenuItem::new(label.clone(), items)
+            .map_err(|_| "At least one entry required")?;
+        Ok(RadioMenuItem {
+            label,
+            submenu,
+            state,
+        })
+    }
+
+    pub fn get_selected_entry_idx(&self) -> usize {
+        self.state.borrow().selected_entry_idx
+    }
+
+    pub fn set_selected_entry_idx(
+        &mut self,
+        selected_entry_idx: usize,
+    ) -> Result<(), &'static str> {
+        let mut state = self.state.borrow_mut();
+        if selected_entry_idx >= state.entries.len() {
+            Err("Selected entry idx must be between 0 and entries.len()")
+        } else {
+            state.selected_entry_idx = selected_entry_idx;
+            Ok(())
+        }
+    }
+
+    pub fn set_text_selected(&mut self, text_selected: String) {
+        self.state.borrow_mut().text_selected = text_selected;
+    }
+
+    pub fn set_text_unselected(&mut self, text_unselected: String) {
+        self.state.borrow_mut().text_unselected = text_unselected;
+    }
+
+    // Submenu with one item per entry, displayed while the item is focused
+    pub fn get_submenu_menu_item(&self) -> &SubmenuMenuItem {
+        &self.submenu
+    }
+}
+
+impl MenuItem for RadioMenuItem {
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        let state = self.state.borrow();
+        write!(
+            writer,
+            "{}: {}",
+            self.label, state.entries[state.selected_entry_idx]
+        )
+    }
+
+    fn write_name(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        writer.write_str(&self.label)
+    }
+
+    fn get_kind(&self, _is_focused: bool) -> ItemKind {
+        ItemKind::Submenu
+    }
+
+    fn get_child_submenu(&self) -> Option<&SubmenuMenuItem> {
+        Some(&self.submenu)
+    }
+
+    fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult {
+        if is_focused {
+            match key.function_key {
+                Some(FunctionKey::ENTER) => {
+                    self.state.borrow_mut().selected_entry_idx =
+                        self.submenu.get_selected_item_idx();
+                    PressResult {
+                        handled: true,
+                        focus: false,
+                    }
+                }
+                Some(FunctionKey::BACK) => PressResult {
+                    handled: true,
+                    focus: false,
+                },
+                _ => {
+                    let submenu_press_result = self.submenu.press(key, true);
+                    PressResult {
+                        handled: submenu_press_result.handled,
+                        focus: true,
+                    }
+                }
+            }
+        } else {
+            match key.function_key {
+                Some(FunctionKey::ENTER) => {
+                    let selected_entry_idx = self.get_selected_entry_idx();
+                    self.submenu
+                        .set_selected_item_idx(selected_entry_idx)
+                        .unwrap();
+                    PressResult {
+                        handled: true,
+                        focus: true,
+                    }
+                }
+                _ => PressResult {
+                    handled: false,
+                    focus: false,
+                },
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_mode_item() -> RadioMenuItem {
+        let entries = vec![
+            String::from("Auto"),
+            String::from("Heat"),
+            String::from("Cool"),
+        ];
+        RadioMenuItem::new(String::from("Mode"), entries).unwrap()
+    }
+
+    fn get_option_labels(item: &RadioMenuItem) -> Vec<String> {
+        let submenu = item.get_submenu_menu_item();
+        (0..submenu.item_count())
+            .map(|item_idx| submenu.get_item(item_idx).unwrap().get_label(false))
+            .collect()
+    }
+
+    #[test]
+    fn new_fails_without_entries() {
+        let item_result = RadioMenuItem::new(String::from("label"), Vec::new());
+        assert_eq!(item_result.err(), Some("At least one entry required"));
+    }
+
+    #[test]
+    fn item_is_usable() {
+        let mut item = create_mode_item();
+        assert_eq!(item.get_label(false), "Mode: Auto");
+        assert_eq!(
+            get_option_labels(&item),
+            vec!["(*) Auto", "( ) Heat", "( ) Cool"]
+        );
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_submenu_menu_item().get_selected_item_idx(), 2);
+        assert_eq!(item.get_selected_entry_idx(), 0);
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_selected_entry_idx(), 2);
+        assert_eq!(item.get_label(false), "Mode: Cool");
+        assert_eq!(
+            get_option_labels(&item),
+            vec!["( ) Auto", "( ) Heat", "(*) Cool"]
+        );
+    }
+
+    #[test]
+    fn back_keeps_selection() {
+        let mut item = create_mode_item();
+        item.set_selected_entry_idx(1).unwrap();
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(item.get_submenu_menu_item().get_selected_item_idx(), 1);
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_selected_entry_idx(), 1);
+        assert_eq!(
+            item.set_selected_entry_idx(3),
+            Err("Selected entry idx must be between 0 and entries.len()")
+        );
+    }
+
+    #[test]
+    fn markers_are_configurable() {
+        let mut item = create_mode_item();
+        item.set_text_selected(String::from("*"));
+        item.set_text_unselected(String::from(" "));
+        assert_eq!(get_option_labels(&item), vec!["* Auto", "  Heat", "  Cool"]);
+    }
+}
@@ -1,91 +1,435 @@
 use crate::keyboard::{FunctionKey, KeyboardKey};
+use crate::menu_items::binding::Binding;
 use crate::menu_items::menu_item::{MenuItem, PressResult};
+use crate::menu_items::overflow_policy::{Boundary, OverflowPolicy};
+use crate::menu_items::range_value::RangeValue;
 use core::fmt::Write;
 use std::any::Any;
 
-pub struct RangeMenuItem {
+enum RangeSteps<T> {
+    Uniform(T),
+    Table(Vec<T>),
+    Function {
+        next: Box<dyn Fn(T) -> T>,
+        prev: Box<dyn Fn(T) -> T>,
+    },
+}
+
+pub enum RangeEditMode {
+    // LEFT/RIGHT select the previous/next value
+    Step,
+    // LEFT/RIGHT select the digit, UP/DOWN change it. Only available for integer and fixed
+    // point values displayed without scale, decimals or formatter, otherwise the item falls
+    // back to Step.
+    Digits,
+}
+
+// Amount of ASCII digits written
+struct DigitCounter(usize);
+
+impl Write for DigitCounter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 += s.chars().filter(char::is_ascii_digit).count();
+        Ok(())
+    }
+}
+
+// Inserts the padding zeros before the first digit, e.g. after the sign
+struct PaddedDigitWriter<'a> {
+    writer: &'a mut dyn Write,
+    padding: usize,
+    // Counted from the last digit
+    cursor_digit: usize,
+    remaining_digits: usize,
+    char_idx: usize,
+    cursor_position: Option<usize>,
+}
+
+impl PaddedDigitWriter<'_> {
+    fn write_digit(&mut self, digit: char) -> core::fmt::Result {
+        self.remaining_digits -= 1;
+        if self.remaining_digits == self.cursor_digit {
+            self.cursor_position = Some(self.char_idx);
+        }
+        self.char_idx += 1;
+        self.writer.write_char(digit)
+    }
+}
+
+impl Write for PaddedDigitWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            if c.is_ascii_digit() {
+                for _padding_idx in 0..core::mem::take(&mut self.padding) {
+                    self.write_digit('0')?;
+                }
+                self.write_digit(c)?;
+            } else {
+                self.char_idx += 1;
+                self.writer.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct RangeMenuItem<T: RangeValue = u32> {
     label: String,
-    value: u32,
-    focused_value: u32,
-    max_value: u32,
-    min_value: u32,
-    step_size: u32,
+    value: T,
+    focused_value: T,
+    max_value: T,
+    min_value: T,
+    steps: RangeSteps<T>,
+    overflow_policy: OverflowPolicy,
+    edit_mode: RangeEditMode,
+    digit_position: u32,
+    // Display options
+    scale: Option<(f64, f64)>,
+    decimals: Option<usize>,
+    unit: String,
+    formatter: Option<Box<dyn Fn(T) -> String>>,
+    // Hooks
+    on_preview: Option<Box<dyn FnMut(T)>>,
+    on_revert: Option<Box<dyn FnMut(T)>>,
+    on_commit: Option<Box<dyn FnMut(T)>>,
+    binding: Option<Box<dyn Binding<T>>>,
 }
 
-impl RangeMenuItem {
+impl<T: RangeValue> RangeMenuItem<T> {
     pub fn new(
         label: String,
-        min_value: u32,
-        max_value: u32,
-        step_size: u32,
-    ) -> Result<RangeMenuItem, &'static str> {
+        min_value: T,
+        max_value: T,
+        step_size: T,
+    ) -> Result<RangeMenuItem<T>, &'static str> {
         if min_value == max_value {
             Err("Min and max value can't be equal")
         } else if min_value > max_value {
             Err("Max value must be bigger than min value")
+        } else if step_size <= T::zero() {
+            Err("Step size must be bigger than zero")
+        } else if min_value.checked_add(step_size) == Some(min_value)
+            || max_value.checked_sub(step_size) == Some(max_value)
+        {
+            // A float step below the precision of the values would never move
+            Err("Step size is too small for the precision of the values")
+        } else if min_value.is_step_aligned(max_value, step_size) {
+            Ok(Self::create(
+                label,
+                min_value,
+                max_value,
+                RangeSteps::Uniform(step_size),
+            ))
         } else {
-            let value_difference = max_value - min_value;
-            let reminder = value_difference % step_size;
-            if reminder == 0 {
-                Ok(RangeMenuItem {
-                    label,
-                    value: min_value,
-                    focused_value: min_value,
-                    min_value,
-                    max_value,
-                    step_size,
-                })
-            } else {
-                Err("The step size doesn't allow traversing min_value to max_value without any reminder")
-            }
+            Err("The step size doesn't allow traversing min_value to max_value without any reminder")
         }
     }
 
-    pub fn get_value(&self) -> u32 {
-        self.value
+    // The table must be sorted in ascending order, e.g. a list of allowed baud rates
+    pub fn new_with_table(label: String, table: Vec<T>) -> Result<RangeMenuItem<T>, &'static str> {
+        if table.len() < 2 {
+            Err("At least two table values required")
+        } else if table.windows(2).any(|values| values[0] >= values[1]) {
+            Err("Table values must be strictly increasing")
+        } else {
+            let min_value = table[0];
+            let max_value = table[table.len() - 1];
+            Ok(Self::create(
+                label,
+                min_value,
+                max_value,
+                RangeSteps::Table(table),
+            ))
+        }
+    }
+
+    // next must return a bigger value and prev a smaller one, e.g. for 1-2-5 sequences.
+    // Results outside of min_value..=max_value are handled by the overflow policy.
+    pub fn new_with_step_fn(
+        label: String,
+        min_value: T,
+        max_value: T,
+        next: Box<dyn Fn(T) -> T>,
+        prev: Box<dyn Fn(T) -> T>,
+    ) -> Result<RangeMenuItem<T>, &'static str> {
+        if min_value == max_value {
+            Err("Min and max value can't be equal")
+        } else if min_value > max_value {
+            Err("Max value must be bigger than min value")
+        } else {
+            Ok(Self::create(
+                label,
+                min_value,
+                max_value,
+                RangeSteps::Function { next, prev },
+            ))
+        }
     }
 
-    pub fn select_next_value(&mut self) {
-        let mut new_value = self.value + self.step_size;
-        if new_value > self.max_value {
-            new_value = self.min_value;
+    fn create(label: String, min_value: T, max_value: T, steps: RangeSteps<T>) -> RangeMenuItem<T> {
+        RangeMenuItem {
+            label,
+            value: min_value,
+            focused_value: min_value,
+            min_value,
+            max_value,
+            steps,
+            overflow_policy: OverflowPolicy::Wrap,
+            edit_mode: RangeEditMode::Step,
+            digit_position: 0,
+            scale: None,
+            decimals: None,
+            unit: String::new(),
+            formatter: None,
+            on_preview: None,
+            on_revert: None,
+            on_commit: None,
+            binding: None,
         }
-        self.value = new_value;
-        self.focused_value = new_value;
     }
 
-    pub fn select_prev_value(&mut self) {
-        let new_value = if self.value == self.min_value {
+    // Bound values outside of min_value..=max_value are clamped, the clamped value is written
+    // back when the item loads it, e.g. on enter
+    pub fn get_value(&self) -> T {
+        match &self.binding {
+            Some(binding) => self.clamp_value(binding.read()),
+            None => self.value,
+        }
+    }
+
+    // Values which can't be compared, i.e. NaN, become min_value
+    fn clamp_value(&self, value: T) -> T {
+        if value >= self.min_value && value <= self.max_value {
+            value
+        } else if value > self.max_value {
             self.max_value
         } else {
-            self.value - self.step_size
-        };
-        self.value = new_value;
-        self.focused_value = new_value;
+            self.min_value
+        }
     }
 
-    pub fn select_focused_next_value(&mut self) {
-        let mut new_value = self.focused_value + self.step_size;
-        if new_value > self.max_value {
-            new_value = self.min_value;
+    pub fn set_binding(&mut self, binding: Box<dyn Binding<T>>) {
+        self.binding = Some(binding);
+        self.load_value();
+    }
+
+    fn load_value(&mut self) {
+        if let Some(binding) = &self.binding {
+            let bound_value = binding.read();
+            self.value = self.clamp_value(bound_value);
+            if self.value != bound_value {
+                self.store_value();
+            }
         }
-        self.focused_value = new_value;
+        self.focused_value = self.value;
     }
 
-    pub fn select_focused_prev_value(&mut self) {
-        let new_value = if self.focused_value == self.min_value {
-            self.max_value
+    fn store_value(&mut self) {
+        if let Some(binding) = &mut self.binding {
+            binding.write(self.value);
+        }
+    }
+
+    // Displayed value is value * factor + offset
+    pub fn set_scale(&mut self, factor: f64, offset: f64) {
+        self.scale = Some((factor, offset));
+    }
+
+    pub fn set_decimals(&mut self, decimals: usize) {
+        self.decimals = Some(decimals);
+    }
+
+    // Appended as is, e.g. " %" results in "Volume: 45 %"
+    pub fn set_unit(&mut self, unit: String) {
+        self.unit = unit;
+    }
+
+    // Replaces the scaling and decimals options. The unit is still appended.
+    pub fn set_formatter(&mut self, formatter: Box<dyn Fn(T) -> String>) {
+        self.formatter = Some(formatter);
+    }
+
+    // Called with the focused value every time it changes while editing
+    pub fn set_on_preview(&mut self, on_preview: Box<dyn FnMut(T)>) {
+        self.on_preview = Some(on_preview);
+    }
+
+    // Called with the restored value when BACK discards the focused value
+    pub fn set_on_revert(&mut self, on_revert: Box<dyn FnMut(T)>) {
+        self.on_revert = Some(on_revert);
+    }
+
+    // Called with the new value when ENTER confirms the focused value
+    pub fn set_on_commit(&mut self, on_commit: Box<dyn FnMut(T)>) {
+        self.on_commit = Some(on_commit);
+    }
+
+    fn write_value(&self, writer: &mut dyn Write, value: T) -> core::fmt::Result {
+        if let Some(formatter) = &self.formatter {
+            writer.write_str(&formatter(value))
+        } else if self.scale.is_some() || self.decimals.is_some() {
+            let (factor, offset) = self.scale.unwrap_or((1.0, 0.0));
+            let display_value = value.to_f64() * factor + offset;
+            if let Some(decimals) = self.decimals {
+                write!(writer, "{:.*}", decimals, display_value)
+            } else {
+                write!(writer, "{}", display_value)
+            }
         } else {
-            self.focused_value - self.step_size
+            write!(writer, "{}", value)
+        }
+    }
+
+    // Writes the focused value zero padded to digit_count, returns the char idx of the digit
+    // at digit_position
+    fn write_digits_label(
+        &self,
+        writer: &mut dyn Write,
+    ) -> Result<Option<usize>, core::fmt::Error> {
+        let mut digit_counter = DigitCounter(0);
+        write!(digit_counter, "{}", self.focused_value)?;
+        let digit_count = (self.digit_count() as usize).max(digit_counter.0);
+
+        write!(writer, "{}: ", self.label)?;
+        let mut digit_writer = PaddedDigitWriter {
+            writer: &mut *writer,
+            padding: digit_count - digit_counter.0,
+            cursor_digit: self.digit_position as usize,
+            remaining_digits: digit_count,
+            char_idx: self.label.chars().count() + 2,
+            cursor_position: None,
+        };
+        write!(digit_writer, "{}", self.focused_value)?;
+        let cursor_position = digit_writer.cursor_position;
+        writer.write_str(&self.unit)?;
+        Ok(cursor_position)
+    }
+
+    pub fn set_edit_mode(&mut self, edit_mode: RangeEditMode) -> Result<(), &'static str> {
+        if matches!(edit_mode, RangeEditMode::Digits) && !self.has_plain_digits() {
+            return Err("Digits can only be edited for integer and fixed point values without scale, decimals or formatter");
+        }
+        self.edit_mode = edit_mode;
+        Ok(())
+    }
+
+    // Whether the digits of the displayed value are the digits changed by digit_step
+    fn has_plain_digits(&self) -> bool {
+        T::digit_step(0).is_some()
+            && self.formatter.is_none()
+            && self.scale.is_none()
+            && self.decimals.is_none()
+    }
+
+    fn is_editing_digits(&self) -> bool {
+        matches!(self.edit_mode, RangeEditMode::Digits) && self.has_plain_digits()
+    }
+
+    // Digit changes must land on a value the steps can reach
+    fn is_reachable(&self, value: T) -> bool {
+        match &self.steps {
+            RangeSteps::Uniform(step_size) => self.min_value.is_step_aligned(value, *step_size),
+            RangeSteps::Table(table) => table.contains(&value),
+            RangeSteps::Function { .. } => true,
+        }
+    }
+
+    // Amount of digits needed to display the biggest value in the range
+    fn digit_count(&self) -> u32 {
+        let max_magnitude = match T::zero().checked_sub(self.min_value) {
+            Some(min_magnitude) if min_magnitude > self.max_value => min_magnitude,
+            _ => self.max_value,
+        };
+        let mut digit_count = 1;
+        while let Some(digit_step) = T::digit_step(digit_count) {
+            if digit_step > max_magnitude {
+                break;
+            }
+            digit_count += 1;
+        }
+        digit_count
+    }
+
+    pub fn set_overflow_policy(&mut self, overflow_policy: OverflowPolicy) {
+        self.overflow_policy = overflow_policy;
+    }
+
+    fn next_value(&mut self, value: T) -> Option<T> {
+        let next_value = match &self.steps {
+            RangeSteps::Uniform(step_size) => value.checked_add(*step_size),
+            RangeSteps::Table(table) => table.iter().copied().find(|entry| *entry > value),
+            RangeSteps::Function { next, .. } => Some(next(value)).filter(|entry| *entry > value),
+        };
+        match next_value {
+            Some(new_value) if new_value <= self.max_value => Some(new_value),
+            _ => self.overflow_policy.overflow(Boundary::Max, self.min_value),
+        }
+    }
+
+    fn prev_value(&mut self, value: T) -> Option<T> {
+        let prev_value = match &self.steps {
+            RangeSteps::Uniform(step_size) => value.checked_sub(*step_size),
+            RangeSteps::Table(table) => table.iter().rev().copied().find(|entry| *entry < value),
+            RangeSteps::Function { prev, .. } => Some(prev(value)).filter(|entry| *entry < value),
         };
-        self.focused_value = new_value;
+        match prev_value {
+            Some(new_value) if new_value >= self.min_value => Some(new_value),
+            _ => self.overflow_policy.overflow(Boundary::Min, self.max_value),
+        }
+    }
+
+    pub fn select_next_value(&mut self) -> bool {
+        self.load_value();
+        if let Some(new_value) = self.next_value(self.value) {
+            self.value = new_value;
+            self.focused_value = new_value;
+            self.store_value();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_prev_value(&mut self) -> bool {
+        self.load_value();
+        if let Some(new_value) = self.prev_value(self.value) {
+            self.value = new_value;
+            self.focused_value = new_value;
+            self.store_value();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_focused_next_value(&mut self) -> bool {
+        if let Some(new_value) = self.next_value(self.focused_value) {
+            self.focused_value = new_value;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_focused_prev_value(&mut self) -> bool {
+        if let Some(new_value) = self.prev_value(self.focused_value) {
+            self.focused_value = new_value;
+            true
+        } else {
+            false
+        }
     }
 
     fn enter(&mut self, is_focused: bool) -> PressResult {
         if is_focused {
             self.value = self.focused_value;
+            self.store_value();
+            if let Some(on_commit) = &mut self.on_commit {
+                on_commit(self.value);
+            }
         } else {
-            self.focused_value = self.value;
+            self.load_value();
+            self.digit_position = 0;
         }
         PressResult {
             focus: !is_focused,
@@ -93,59 +437,149 @@ impl RangeMenuItem {
         }
     }
 
-    fn back(&mut self) -> PressResult {
+    fn back(&mut self, is_focused: bool) -> PressResult {
         self.focused_value = self.value;
+        if is_focused {
+            if let Some(on_revert) = &mut self.on_revert {
+                on_revert(self.value);
+            }
+        }
         PressResult {
             handled: true,
             focus: false,
         }
     }
 
+    fn preview(&mut self) {
+        if let Some(on_preview) = &mut self.on_preview {
+            on_preview(self.focused_value);
+        }
+    }
+
     fn left(&mut self) -> PressResult {
-        self.select_focused_prev_value();
+        let handled = self.select_focused_prev_value();
+        if handled {
+            self.preview();
+        }
         PressResult {
             focus: true,
-            handled: true,
+            handled,
         }
     }
 
     fn right(&mut self) -> PressResult {
-        self.select_focused_next_value();
+        let handled = self.select_focused_next_value();
+        if handled {
+            self.preview();
+        }
         PressResult {
             focus: true,
-            handled: true,
+            handled,
         }
     }
-}
 
-impl MenuItem for RangeMenuItem {
-    fn get_label(&self, is_focused: bool) -> String {
-        let value = if is_focused {
-            &self.focused_value
+    fn select_digit(&mut self, more_significant: bool) -> PressResult {
+        let handled = if more_significant {
+            if self.digit_position + 1 < self.digit_count() {
+                self.digit_position += 1;
+                true
+            } else {
+                false
+            }
+        } else if let Some(digit_position) = self.digit_position.checked_sub(1) {
+            self.digit_position = digit_position;
+            true
         } else {
-            &self.value
+            false
         };
+        PressResult {
+            focus: true,
+            handled,
+        }
+    }
 
-        let mut label_str: String = String::new();
-        write!(label_str, "{}: {}", self.label, value).unwrap();
-        label_str
+    fn change_digit(&mut self, increase: bool) -> PressResult {
+        let new_value = T::digit_step(self.digit_position).and_then(|digit_step| {
+            if increase {
+                self.focused_value.checked_add(digit_step)
+            } else {
+                self.focused_value.checked_sub(digit_step)
+            }
+        });
+        let handled = match new_value {
+            Some(new_value)
+                if new_value >= self.min_value
+                    && new_value <= self.max_value
+                    && self.is_reachable(new_value) =>
+            {
+                self.focused_value = new_value;
+                self.preview();
+                true
+            }
+            _ => false,
+        };
+        PressResult {
+            focus: true,
+            handled,
+        }
+    }
+}
+
+impl<T: RangeValue> MenuItem for RangeMenuItem<T> {
+    fn write_label(&self, writer: &mut dyn Write, is_focused: bool) -> core::fmt::Result {
+        if is_focused && self.is_editing_digits() {
+            self.write_digits_label(writer).map(|_| ())
+        } else {
+            let value = if is_focused {
+                self.focused_value
+            } else {
+                self.get_value()
+            };
+            write!(writer, "{}: ", self.label)?;
+            self.write_value(writer, value)?;
+            writer.write_str(&self.unit)
+        }
+    }
+
+    fn get_cursor_position(&self, is_focused: bool) -> Option<usize> {
+        if is_focused && self.is_editing_digits() {
+            // Only the position is needed, the label itself is discarded
+            self.write_digits_label(&mut DigitCounter(0)).ok().flatten()
+        } else {
+            None
+        }
     }
 
     fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult {
+        let edit_mode = if self.is_editing_digits() {
+            RangeEditMode::Digits
+        } else {
+            RangeEditMode::Step
+        };
+        // Unhandled keys must not leave the digit being edited
+        let keep_focus = is_focused && matches!(edit_mode, RangeEditMode::Digits);
         if let Some(function_key) = &key.function_key {
-            match function_key {
-                FunctionKey::ENTER => self.enter(is_focused),
-                FunctionKey::BACK => self.back(),
-                FunctionKey::LEFT => self.left(),
-                FunctionKey::RIGHT => self.right(),
+            match (function_key, edit_mode) {
+                (FunctionKey::ENTER, _) => self.enter(is_focused),
+                (FunctionKey::BACK, _) => self.back(is_focused),
+                (FunctionKey::LEFT, RangeEditMode::Step) => self.left(),
+                (FunctionKey::RIGHT, RangeEditMode::Step) => self.right(),
+                (FunctionKey::LEFT, RangeEditMode::Digits) if is_focused => self.select_digit(true),
+                (FunctionKey::RIGHT, RangeEditMode::Digits) if is_focused => {
+                    self.select_digit(false)
+                }
+                (FunctionKey::UP, RangeEditMode::Digits) if is_focused => self.change_digit(true),
+                (FunctionKey::DOWN, RangeEditMode::Digits) if is_focused => {
+                    self.change_digit(false)
+                }
                 _ => PressResult {
-                    focus: false,
+                    focus: keep_focus,
                     handled: false,
                 },
             }
         } else {
             PressResult {
-                focus: false,
+                focus: keep_focus,
                 handled: false,
             }
         }
@@ -163,6 +597,9 @@ impl MenuItem for RangeMenuItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::menu_items::range_value::FixedPoint;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
 
     fn assert_new_error(expected_error_msg: &str, min_value: u32, max_value: u32, step_size: u32) {
         let range_menu_item_result: Result<RangeMenuItem, &str> =
@@ -390,4 +827,568 @@ mod tests {
         assert_eq!(item.get_label(true), "label: 20");
         assert_eq!(item.get_value(), 20);
     }
+
+    fn press_key(item: &mut dyn MenuItem, function_key: FunctionKey, is_focused: bool) {
+        item.press(&KeyboardKey::new(Some(function_key), None), is_focused);
+    }
+
+    #[test]
+    fn new_fails_zero_step_size() {
+        assert_new_error("Step size must be bigger than zero", 0, 100, 0);
+    }
+
+    #[test]
+    fn signed_item_wraps_around() {
+        let mut item: RangeMenuItem<i8> =
+            RangeMenuItem::new(String::from("Offset"), -10, 10, 5).unwrap();
+        assert_eq!(item.get_label(false), "Offset: -10");
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::LEFT, true);
+        assert_eq!(item.get_label(true), "Offset: 10");
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        assert_eq!(item.get_label(true), "Offset: -10");
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_value(), 0);
+    }
+
+    #[test]
+    fn item_does_not_overflow_type_limits() {
+        let mut item: RangeMenuItem<u8> =
+            RangeMenuItem::new(String::from("Register"), 5, 255, 10).unwrap();
+        item.select_prev_value();
+        assert_eq!(item.get_value(), 255);
+        item.select_next_value();
+        assert_eq!(item.get_value(), 5);
+
+        let mut item: RangeMenuItem<i8> =
+            RangeMenuItem::new(String::from("Register"), i8::MIN, i8::MAX, 1).unwrap();
+        item.select_prev_value();
+        assert_eq!(item.get_value(), i8::MAX);
+        item.select_next_value();
+        assert_eq!(item.get_value(), i8::MIN);
+    }
+
+    #[test]
+    fn float_item_reaches_max_value() {
+        let mut item: RangeMenuItem<f32> =
+            RangeMenuItem::new(String::from("Gain"), 0.0, 1.0, 0.1).unwrap();
+        for _ in 0..3 {
+            item.select_next_value();
+        }
+        assert_eq!(item.get_label(false), "Gain: 0.3");
+        for _ in 0..7 {
+            item.select_next_value();
+        }
+        assert_eq!(item.get_value(), 1.0);
+        item.select_next_value();
+        assert_eq!(item.get_value(), 0.0);
+        item.select_prev_value();
+        assert_eq!(item.get_value(), 1.0);
+    }
+
+    #[test]
+    fn fixed_point_item_works() {
+        let mut item: RangeMenuItem<FixedPoint<1>> = RangeMenuItem::new(
+            String::from("Gain"),
+            FixedPoint::from_raw(-10),
+            FixedPoint::from_raw(10),
+            FixedPoint::from_raw(5),
+        )
+        .unwrap();
+        assert_eq!(item.get_label(false), "Gain: -1.0");
+
+        item.select_next_value();
+        assert_eq!(item.get_label(false), "Gain: -0.5");
+        item.select_prev_value();
+        item.select_prev_value();
+        assert_eq!(item.get_label(false), "Gain: 1.0");
+        assert_eq!(item.get_value().get_raw(), 10);
+    }
+
+    #[test]
+    fn float_new_fails_invalid_step_size() {
+        let item_result = RangeMenuItem::new(String::from("label"), 0.0f64, 1.0, 0.3);
+        assert!(item_result.is_err());
+    }
+
+    #[test]
+    fn float_item_supports_small_steps_and_big_values() {
+        let mut item: RangeMenuItem<f32> =
+            RangeMenuItem::new(String::from("Offset"), 0.0, 1e-5, 1e-6).unwrap();
+        item.select_next_value();
+        item.select_next_value();
+        assert!((item.get_value() - 2e-6).abs() < 1e-12);
+
+        let mut item: RangeMenuItem<f32> =
+            RangeMenuItem::new(String::from("Freq"), 1e30, 2e30, 1e29).unwrap();
+        item.select_next_value();
+        assert!(item.get_value() > 1e30);
+        item.select_prev_value();
+        item.select_prev_value();
+        assert_eq!(item.get_value(), 2e30);
+
+        assert!(RangeMenuItem::new(String::from("Freq"), 1e8f32, 2e8, 1e-3).is_err());
+    }
+
+    #[test]
+    fn unit_is_appended() {
+        let mut item: RangeMenuItem<u8> =
+            RangeMenuItem::new(String::from("Volume"), 0, 100, 5).unwrap();
+        item.set_unit(String::from(" %"));
+        item.select_prev_value();
+        assert_eq!(item.get_label(false), "Volume: 100 %");
+    }
+
+    #[test]
+    fn value_is_scaled() {
+        let mut item: RangeMenuItem<u16> =
+            RangeMenuItem::new(String::from("Delay"), 0, 100, 5).unwrap();
+        item.set_scale(0.1, 0.0);
+        item.set_decimals(1);
+        item.set_unit(String::from(" s"));
+        assert_eq!(item.get_label(false), "Delay: 0.0 s");
+        for _ in 0..3 {
+            item.select_next_value();
+        }
+        assert_eq!(item.get_label(false), "Delay: 1.5 s");
+        assert_eq!(item.get_value(), 15);
+
+        let mut item: RangeMenuItem<u8> =
+            RangeMenuItem::new(String::from("Temp"), 0, 200, 1).unwrap();
+        item.set_scale(0.5, -40.0);
+        item.set_decimals(1);
+        item.set_unit(String::from(" °C"));
+        for _ in 0..74 {
+            item.select_next_value();
+        }
+        assert_eq!(item.get_label(false), "Temp: -3.0 °C");
+    }
+
+    #[test]
+    fn formatter_is_used() {
+        let mut item: RangeMenuItem = RangeMenuItem::new(String::from("Baud"), 0, 2, 1).unwrap();
+        item.set_formatter(Box::new(|value| match value {
+            0 => String::from("low"),
+            1 => String::from("mid"),
+            _ => String::from("high"),
+        }));
+        item.set_unit(String::from("!"));
+        assert_eq!(item.get_label(false), "Baud: low!");
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true);
+        assert_eq!(item.get_label(true), "Baud: high!");
+        assert_eq!(item.get_label(false), "Baud: low!");
+    }
+
+    #[test]
+    fn clamp_reports_unhandled_at_boundaries() {
+        let mut item: RangeMenuItem = RangeMenuItem::new(String::from("label"), 0, 40, 20).unwrap();
+        item.set_overflow_policy(OverflowPolicy::Clamp);
+        press_key(&mut item, FunctionKey::ENTER, false);
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_label(true), "label: 0");
+
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::RIGHT), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_label(true), "label: 40");
+
+        assert!(!item.select_prev_value());
+        assert_eq!(item.get_value(), 0);
+    }
+
+    #[test]
+    fn clamp_with_event_notifies_boundary() {
+        let boundaries = Rc::new(RefCell::new(Vec::new()));
+        let boundaries_clone = Rc::clone(&boundaries);
+        let mut item: RangeMenuItem<i8> =
+            RangeMenuItem::new(String::from("Speed"), -1, 1, 1).unwrap();
+        item.set_overflow_policy(OverflowPolicy::ClampWithEvent(Box::new(move |boundary| {
+            boundaries_clone.borrow_mut().push(boundary)
+        })));
+
+        assert!(!item.select_prev_value());
+        assert!(item.select_next_value());
+        assert!(item.select_next_value());
+        assert!(!item.select_next_value());
+        assert_eq!(item.get_value(), 1);
+        assert_eq!(*boundaries.borrow(), vec![Boundary::Min, Boundary::Max]);
+    }
+
+    #[test]
+    fn new_with_table_fails_invalid_table() {
+        let item_result = RangeMenuItem::<u32>::new_with_table(String::from("label"), vec![1]);
+        assert_eq!(
+            item_result.err(),
+            Some("At least two table values required")
+        );
+
+        let item_result =
+            RangeMenuItem::<u32>::new_with_table(String::from("label"), vec![1, 5, 2]);
+        assert_eq!(
+            item_result.err(),
+            Some("Table values must be strictly increasing")
+        );
+    }
+
+    #[test]
+    fn table_item_works_as_expected() {
+        let mut item: RangeMenuItem =
+            RangeMenuItem::new_with_table(String::from("Baud"), vec![1200, 9600, 19200, 115200])
+                .unwrap();
+        assert_eq!(item.get_label(false), "Baud: 1200");
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::LEFT, true);
+        assert_eq!(item.get_label(true), "Baud: 115200");
+        press_key(&mut item, FunctionKey::LEFT, true);
+        assert_eq!(item.get_label(true), "Baud: 19200");
+        assert_eq!(item.get_value(), 1200);
+
+        press_key(&mut item, FunctionKey::BACK, true);
+        assert_eq!(item.get_label(true), "Baud: 1200");
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        assert_eq!(item.get_label(true), "Baud: 9600");
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_value(), 9600);
+
+        item.set_overflow_policy(OverflowPolicy::Clamp);
+        assert!(item.select_prev_value());
+        assert!(!item.select_prev_value());
+        assert_eq!(item.get_value(), 1200);
+    }
+
+    fn next_1_2_5(value: u32) -> u32 {
+        let mut magnitude = 1;
+        while magnitude * 10 <= value {
+            magnitude *= 10;
+        }
+        match value / magnitude {
+            1 => 2 * magnitude,
+            2..=4 => 5 * magnitude,
+            _ => 10 * magnitude,
+        }
+    }
+
+    fn prev_1_2_5(value: u32) -> u32 {
+        let mut magnitude = 1;
+        while magnitude * 10 < value {
+            magnitude *= 10;
+        }
+        match value.div_ceil(magnitude) {
+            0..=2 => magnitude,
+            3..=5 => 2 * magnitude,
+            _ => 5 * magnitude,
+        }
+    }
+
+    #[test]
+    fn step_fn_item_works_as_expected() {
+        let mut item: RangeMenuItem = RangeMenuItem::new_with_step_fn(
+            String::from("Range"),
+            1,
+            100,
+            Box::new(next_1_2_5),
+            Box::new(prev_1_2_5),
+        )
+        .unwrap();
+
+        let mut values = Vec::new();
+        for _ in 0..7 {
+            values.push(item.get_value());
+            item.select_next_value();
+        }
+        assert_eq!(values, vec![1, 2, 5, 10, 20, 50, 100]);
+        assert_eq!(item.get_value(), 1);
+
+        item.select_prev_value();
+        assert_eq!(item.get_value(), 100);
+        item.select_prev_value();
+        assert_eq!(item.get_value(), 50);
+        item.select_prev_value();
+        assert_eq!(item.get_value(), 20);
+    }
+
+    #[test]
+    fn step_fn_item_stops_if_no_progress() {
+        let mut item: RangeMenuItem = RangeMenuItem::new_with_step_fn(
+            String::from("Frequency"),
+            1,
+            1024,
+            Box::new(|value| value * 2),
+            Box::new(|value| value / 2),
+        )
+        .unwrap();
+        item.set_overflow_policy(OverflowPolicy::Clamp);
+
+        assert!(!item.select_prev_value());
+        for _ in 0..10 {
+            assert!(item.select_next_value());
+        }
+        assert_eq!(item.get_value(), 1024);
+        assert!(!item.select_next_value());
+    }
+
+    #[test]
+    fn hooks_are_called() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut item: RangeMenuItem =
+            RangeMenuItem::new(String::from("Brightness"), 0, 100, 10).unwrap();
+        let preview_events = Rc::clone(&events);
+        item.set_on_preview(Box::new(move |value| {
+            preview_events.borrow_mut().push(("preview", value))
+        }));
+        let revert_events = Rc::clone(&events);
+        item.set_on_revert(Box::new(move |value| {
+            revert_events.borrow_mut().push(("revert", value))
+        }));
+        let commit_events = Rc::clone(&events);
+        item.set_on_commit(Box::new(move |value| {
+            commit_events.borrow_mut().push(("commit", value))
+        }));
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::BACK, true);
+        assert_eq!(
+            *events.borrow(),
+            vec![("preview", 10), ("preview", 20), ("revert", 0)]
+        );
+
+        events.borrow_mut().clear();
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::LEFT, true);
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(*events.borrow(), vec![("preview", 100), ("commit", 100)]);
+
+        events.borrow_mut().clear();
+        item.set_overflow_policy(OverflowPolicy::Clamp);
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        press_key(&mut item, FunctionKey::BACK, true);
+        press_key(&mut item, FunctionKey::BACK, false);
+        assert_eq!(*events.borrow(), vec![("revert", 100)]);
+    }
+
+    #[test]
+    fn digits_mode_changes_single_digits() {
+        let mut item: RangeMenuItem =
+            RangeMenuItem::new(String::from("Freq"), 0, 150000, 1).unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        assert_eq!(item.get_label(false), "Freq: 0");
+        assert_eq!(item.get_cursor_position(false), None);
+
+        press_key(&mut item, FunctionKey::ENTER, false);
+        assert_eq!(item.get_label(true), "Freq: 000000");
+        assert_eq!(item.get_cursor_position(true), Some(11));
+
+        press_key(&mut item, FunctionKey::UP, true);
+        assert_eq!(item.get_label(true), "Freq: 000001");
+        press_key(&mut item, FunctionKey::LEFT, true);
+        press_key(&mut item, FunctionKey::LEFT, true);
+        assert_eq!(item.get_cursor_position(true), Some(9));
+        for _ in 0..12 {
+            press_key(&mut item, FunctionKey::UP, true);
+        }
+        assert_eq!(item.get_label(true), "Freq: 001201");
+
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        for _ in 0..3 {
+            press_key(&mut item, FunctionKey::DOWN, true);
+        }
+        assert_eq!(item.get_label(true), "Freq: 001171");
+        assert_eq!(item.get_cursor_position(true), Some(10));
+
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_value(), 1171);
+        assert_eq!(item.get_label(false), "Freq: 1171");
+    }
+
+    #[test]
+    fn digits_mode_respects_min_max() {
+        let mut item: RangeMenuItem =
+            RangeMenuItem::new(String::from("Freq"), 0, 150000, 1).unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        press_key(&mut item, FunctionKey::ENTER, false);
+
+        for _ in 0..5 {
+            press_key(&mut item, FunctionKey::LEFT, true);
+        }
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_cursor_position(true), Some(6));
+
+        press_key(&mut item, FunctionKey::UP, true);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_label(true), "Freq: 100000");
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+
+        press_key(&mut item, FunctionKey::BACK, true);
+        assert_eq!(item.get_value(), 0);
+    }
+
+    #[test]
+    fn digits_mode_works_with_signed_and_fixed_point() {
+        let mut item: RangeMenuItem<i16> =
+            RangeMenuItem::new(String::from("Offset"), -500, 50, 1).unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        press_key(&mut item, FunctionKey::ENTER, false);
+        press_key(&mut item, FunctionKey::LEFT, true);
+        press_key(&mut item, FunctionKey::DOWN, true);
+        assert_eq!(item.get_label(true), "Offset: -500");
+        press_key(&mut item, FunctionKey::UP, true);
+        assert_eq!(item.get_label(true), "Offset: -490");
+        assert_eq!(item.get_cursor_position(true), Some(10));
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(item.get_value(), -490);
+
+        let mut item: RangeMenuItem<FixedPoint<1>> = RangeMenuItem::new(
+            String::from("Gain"),
+            FixedPoint::from_raw(0),
+            FixedPoint::from_raw(200),
+            FixedPoint::from_raw(1),
+        )
+        .unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        press_key(&mut item, FunctionKey::ENTER, false);
+        assert_eq!(item.get_label(true), "Gain: 00.0");
+        press_key(&mut item, FunctionKey::LEFT, true);
+        press_key(&mut item, FunctionKey::UP, true);
+        assert_eq!(item.get_label(true), "Gain: 01.0");
+        assert_eq!(item.get_cursor_position(true), Some(7));
+    }
+
+    #[test]
+    fn digits_mode_needs_plain_digits() {
+        let mut item: RangeMenuItem<f32> =
+            RangeMenuItem::new(String::from("Gain"), 0.0, 10.0, 0.5).unwrap();
+        assert!(item.set_edit_mode(RangeEditMode::Digits).is_err());
+
+        let mut item: RangeMenuItem = RangeMenuItem::new(String::from("Temp"), 0, 100, 1).unwrap();
+        item.set_scale(0.5, 0.0);
+        assert!(item.set_edit_mode(RangeEditMode::Digits).is_err());
+
+        let mut item: RangeMenuItem = RangeMenuItem::new(String::from("Temp"), 0, 100, 1).unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        item.set_formatter(Box::new(|value| format!("{:x}", value)));
+        press_key(&mut item, FunctionKey::ENTER, false);
+        assert_eq!(item.get_cursor_position(true), None);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        assert_eq!(item.get_label(true), "Temp: 1");
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true),
+            PressResult {
+                focus: false,
+                handled: false
+            }
+        );
+    }
+
+    #[test]
+    fn digits_mode_stays_on_steps() {
+        let mut item: RangeMenuItem =
+            RangeMenuItem::new(String::from("Volume"), 0, 100, 5).unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        press_key(&mut item, FunctionKey::ENTER, false);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        press_key(&mut item, FunctionKey::LEFT, true);
+        press_key(&mut item, FunctionKey::UP, true);
+        assert_eq!(item.get_label(true), "Volume: 010");
+
+        let mut item: RangeMenuItem =
+            RangeMenuItem::new_with_table(String::from("Baud"), vec![1200, 2400, 4800, 9600])
+                .unwrap();
+        item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        press_key(&mut item, FunctionKey::ENTER, false);
+        for _ in 0..3 {
+            press_key(&mut item, FunctionKey::LEFT, true);
+        }
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(item.get_label(true), "Baud: 1200");
+    }
+
+    #[test]
+    fn bound_value_is_read_and_written() {
+        let volume = Rc::new(Cell::new(40u32));
+        let mut item = RangeMenuItem::new(String::from("Volume"), 0, 100, 10).unwrap();
+        item.set_binding(Box::new(Rc::clone(&volume)));
+        assert_eq!(item.get_label(false), "Volume: 40");
+
+        volume.set(70);
+        assert_eq!(item.get_label(false), "Volume: 70");
+        press_key(&mut item, FunctionKey::ENTER, false);
+        volume.set(20);
+        press_key(&mut item, FunctionKey::RIGHT, true);
+        assert_eq!(item.get_label(true), "Volume: 80");
+        assert_eq!(volume.get(), 20);
+        press_key(&mut item, FunctionKey::ENTER, true);
+        assert_eq!(volume.get(), 80);
+
+        volume.set(200);
+        assert_eq!(item.get_value(), 100);
+        assert_eq!(volume.get(), 200);
+        assert!(item.select_prev_value());
+        assert_eq!(volume.get(), 90);
+
+        let gain = Rc::new(Cell::new(f32::NAN));
+        let mut item = RangeMenuItem::new(String::from("Gain"), -1.0, 1.0, 0.5).unwrap();
+        item.set_binding(Box::new(Rc::clone(&gain)));
+        assert_eq!(gain.get(), -1.0);
+    }
 }
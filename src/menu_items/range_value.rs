@@ -0,0 +1,253 @@
+use core::fmt::{Display, Formatter};
+
+pub trait RangeValue: Copy + PartialOrd + Display + 'static {
+    fn zero() -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    // Whether `other` can be reached from `self` by adding or subtracting whole `step`s
+    fn is_step_aligned(self, other: Self, step: Self) -> bool;
+    fn to_f64(self) -> f64;
+    // Value of a one at the given digit position of the displayed value, i.e. 10^position.
+    // None if the position doesn't exist or the type has no fixed digit positions.
+    fn digit_step(position: u32) -> Option<Self>;
+}
+
+macro_rules! impl_integer_range_value {
+    ($($t:ty),*) => {
+        $(
+            impl RangeValue for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn is_step_aligned(self, other: Self, step: Self) -> bool {
+                    self.rem_euclid(step) == other.rem_euclid(step)
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn digit_step(position: u32) -> Option<Self> {
+                    <$t>::checked_pow(10, position)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_range_value!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+// Float results are rounded relative to the added value, so that repeated steps (e.g. 0.1)
+// don't drift away from the values reachable from min_value, which would break the
+// wrap-around at max_value. $digits decimal digits below the leading digit of the added value
+// are kept, results too big to carry them are left as they are.
+macro_rules! impl_float_range_value {
+    ($($t:ty => $digits:expr, $max_exact:expr),*) => {
+        $(
+            impl RangeValue for $t {
+                fn zero() -> Self {
+                    0.0
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    let result = round_relative!($t, self + rhs, rhs, $digits, $max_exact);
+                    if result.is_finite() {
+                        Some(result)
+                    } else {
+                        None
+                    }
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    let result = round_relative!($t, self - rhs, rhs, $digits, $max_exact);
+                    if result.is_finite() {
+                        Some(result)
+                    } else {
+                        None
+                    }
+                }
+
+                fn is_step_aligned(self, other: Self, step: Self) -> bool {
+                    let step_count = (other - self) / step;
+                    (step_count - step_count.round()).abs() < (10.0 as $t).powi(-$digits)
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                // The displayed digits of a float don't have fixed positions
+                fn digit_step(_position: u32) -> Option<Self> {
+                    None
+                }
+            }
+        )*
+    };
+}
+
+// Powers of ten are multiplied or divided by exactly, never by their inexact inverse
+macro_rules! round_relative {
+    ($t:ty, $value:expr, $reference:expr, $digits:expr, $max_exact:expr) => {{
+        let value: $t = $value;
+        let reference: $t = $reference;
+        if !value.is_finite() || reference == 0.0 {
+            value
+        } else {
+            let exponent = $digits - reference.abs().log10().floor() as i32;
+            let power = (10.0 as $t).powi(exponent.abs());
+            let scaled = if exponent >= 0 {
+                value * power
+            } else {
+                value / power
+            };
+            if !scaled.is_finite() || scaled.abs() >= $max_exact {
+                value
+            } else if exponent >= 0 {
+                scaled.round() / power
+            } else {
+                scaled.round() * power
+            }
+        }
+    }};
+}
+
+impl_float_range_value!(f32 => 5, 16_777_216.0, f64 => 9, 9_007_199_254_740_992.0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedPoint<const DECIMALS: u32> {
+    raw: i64,
+}
+
+impl<const DECIMALS: u32> FixedPoint<DECIMALS> {
+    pub const fn from_raw(raw: i64) -> Self {
+        Self { raw }
+    }
+
+    pub const fn get_raw(&self) -> i64 {
+        self.raw
+    }
+}
+
+impl<const DECIMALS: u32> Display for FixedPoint<DECIMALS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let sign = if self.raw < 0 { "-" } else { "" };
+        let raw = self.raw.unsigned_abs();
+        if DECIMALS == 0 {
+            write!(f, "{}{}", sign, raw)
+        } else {
+            let divisor = 10u64.pow(DECIMALS);
+            write!(
+                f,
+                "{}{}.{:0width$}",
+                sign,
+                raw / divisor,
+                raw % divisor,
+                width = DECIMALS as usize
+            )
+        }
+    }
+}
+
+impl<const DECIMALS: u32> RangeValue for FixedPoint<DECIMALS> {
+    fn zero() -> Self {
+        Self::from_raw(0)
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.raw.checked_add(rhs.raw).map(Self::from_raw)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.raw.checked_sub(rhs.raw).map(Self::from_raw)
+    }
+
+    fn is_step_aligned(self, other: Self, step: Self) -> bool {
+        self.raw.is_step_aligned(other.raw, step.raw)
+    }
+
+    fn to_f64(self) -> f64 {
+        self.raw as f64 / 10f64.powi(DECIMALS as i32)
+    }
+
+    // Position 0 is the last decimal
+    fn digit_step(position: u32) -> Option<Self> {
+        10i64.checked_pow(position).map(Self::from_raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_point_is_displayed_with_decimals() {
+        assert_eq!(FixedPoint::<1>::from_raw(15).to_string(), "1.5");
+        assert_eq!(FixedPoint::<2>::from_raw(5).to_string(), "0.05");
+        assert_eq!(FixedPoint::<1>::from_raw(-5).to_string(), "-0.5");
+        assert_eq!(FixedPoint::<1>::from_raw(-30).to_string(), "-3.0");
+        assert_eq!(FixedPoint::<0>::from_raw(-7).to_string(), "-7");
+    }
+
+    #[test]
+    fn step_alignment_works() {
+        assert!(0u32.is_step_aligned(100, 20));
+        assert!(!0u32.is_step_aligned(100, 9));
+        assert!((-10i8).is_step_aligned(10, 5));
+        assert!(i8::MIN.is_step_aligned(i8::MAX, 1));
+        assert!(0.0f64.is_step_aligned(1.0, 0.1));
+        assert!(!0.0f32.is_step_aligned(1.0, 0.3));
+        assert!(FixedPoint::<1>::from_raw(-10)
+            .is_step_aligned(FixedPoint::from_raw(10), FixedPoint::from_raw(5)));
+    }
+
+    #[test]
+    fn float_steps_dont_drift() {
+        let mut value = 0.0f64;
+        for _ in 0..10 {
+            value = RangeValue::checked_add(value, 0.1).unwrap();
+        }
+        assert_eq!(value, 1.0);
+        assert_eq!(
+            RangeValue::checked_add(0.1f64, 0.2).unwrap().to_string(),
+            "0.3"
+        );
+    }
+
+    #[test]
+    fn float_rounding_is_relative_to_step() {
+        let mut value = 0.0f32;
+        for _ in 0..3 {
+            value = RangeValue::checked_add(value, 1e-6).unwrap();
+        }
+        assert!((value - 3e-6).abs() < 1e-12);
+        assert_eq!(RangeValue::checked_add(1e30f32, 1e29), Some(1.1e30));
+        assert!(RangeValue::checked_sub(3e38f32, 1e38).is_some());
+        assert_eq!(RangeValue::checked_add(f32::MAX, f32::MAX), None);
+        assert_eq!(
+            RangeValue::checked_add(1000.0f64, 2.5e6)
+                .unwrap()
+                .to_string(),
+            "2501000"
+        );
+    }
+
+    #[test]
+    fn digit_step_works() {
+        assert_eq!(u8::digit_step(2), Some(100));
+        assert_eq!(u8::digit_step(3), None);
+        assert_eq!(f32::digit_step(0), None);
+        assert_eq!(
+            FixedPoint::<2>::digit_step(1),
+            Some(FixedPoint::from_raw(10))
+        );
+    }
+}
@@ -1,13 +1,72 @@
 use crate::keyboard::{FunctionKey, KeyboardKey};
-use crate::menu_items::menu_item::{MenuItem, PressResult};
+use crate::menu_items::menu_item::{ItemKind, MenuItem, PressResult};
+use core::fmt::Write;
 use std::any::Any;
 
+// Items produced on demand, e.g. one per stored record. Only the displayed rows are labelled
+// and only the selected item is created.
+pub trait SubmenuSource {
+    fn count(&self) -> usize;
+    fn get_label(&self, idx: usize) -> String;
+    // Can be implemented to render without allocating
+    fn write_label(&self, writer: &mut dyn Write, idx: usize) -> core::fmt::Result {
+        writer.write_str(&self.get_label(idx))
+    }
+    fn create_item(&self, idx: usize) -> Box<dyn MenuItem>;
+    // Called every time the submenu is entered
+    fn refresh(&mut self) {}
+}
+
+// Displayed instead of the items of an empty source
+struct EmptyMenuItem;
+
+impl MenuItem for EmptyMenuItem {
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        writer.write_char('-')
+    }
+
+    fn press(&mut self, _key: &KeyboardKey, _is_focused: bool) -> PressResult {
+        PressResult {
+            handled: false,
+            focus: false,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+enum SubmenuItems {
+    Static(Vec<Box<dyn MenuItem>>),
+    Source {
+        source: Box<dyn SubmenuSource>,
+        // Created from the source for selected_item_idx
+        selected_item: Box<dyn MenuItem>,
+    },
+}
+
+pub enum SelectionPolicy {
+    // Entering the submenu again selects the item selected when it was left
+    Remember,
+    // Entering the submenu always selects the first item
+    Reset,
+}
+
 pub struct SubmenuMenuItem {
     label: String,
-    items: Vec<Box<dyn MenuItem>>,
+    items: SubmenuItems,
+    selection_policy: SelectionPolicy,
     // View state
     selected_item_idx: usize,
     is_focused: bool,
+    // Hooks
+    on_enter: Option<Box<dyn FnMut()>>,
+    on_exit: Option<Box<dyn FnMut()>>,
 }
 
 #[derive(Debug)]
@@ -17,31 +76,122 @@ pub enum NewError {
 
 impl SubmenuMenuItem {
     pub fn new(label: String, items: Vec<Box<dyn MenuItem>>) -> Result<SubmenuMenuItem, NewError> {
-        if items.len() == 0 {
+        if items.is_empty() {
             Err(NewError::InvalidItemsLength)
         } else {
             Ok(SubmenuMenuItem {
                 label,
-                items,
+                items: SubmenuItems::Static(items),
+                selection_policy: SelectionPolicy::Remember,
                 selected_item_idx: 0,
                 is_focused: false,
+                on_enter: None,
+                on_exit: None,
             })
         }
     }
 
+    pub fn new_with_source(label: String, source: Box<dyn SubmenuSource>) -> SubmenuMenuItem {
+        let selected_item = Self::create_source_item(&*source, 0);
+        SubmenuMenuItem {
+            label,
+            items: SubmenuItems::Source {
+                source,
+                selected_item,
+            },
+            selection_policy: SelectionPolicy::Remember,
+            selected_item_idx: 0,
+            is_focused: false,
+            on_enter: None,
+            on_exit: None,
+        }
+    }
+
+    pub fn set_selection_policy(&mut self, selection_policy: SelectionPolicy) {
+        self.selection_policy = selection_policy;
+    }
+
+    // Called when ENTER opens the submenu, before a source is refreshed
+    pub fn set_on_enter(&mut self, on_enter: Box<dyn FnMut()>) {
+        self.on_enter = Some(on_enter);
+    }
+
+    // Called when BACK leaves the submenu
+    pub fn set_on_exit(&mut self, on_exit: Box<dyn FnMut()>) {
+        self.on_exit = Some(on_exit);
+    }
+
+    fn create_source_item(source: &dyn SubmenuSource, idx: usize) -> Box<dyn MenuItem> {
+        if idx < source.count() {
+            source.create_item(idx)
+        } else {
+            Box::new(EmptyMenuItem)
+        }
+    }
+
     pub fn get_selected_item(&self) -> &dyn MenuItem {
-        &*self.items[self.selected_item_idx]
+        match &self.items {
+            SubmenuItems::Static(items) => &*items[self.selected_item_idx],
+            SubmenuItems::Source { selected_item, .. } => &**selected_item,
+        }
     }
 
     pub fn get_mut_selected_item(&mut self) -> &mut dyn MenuItem {
-        &mut *self.items[self.selected_item_idx]
+        match &mut self.items {
+            SubmenuItems::Static(items) => &mut *items[self.selected_item_idx],
+            SubmenuItems::Source { selected_item, .. } => &mut **selected_item,
+        }
+    }
+
+    fn select(&mut self, selected_item_idx: usize) {
+        self.selected_item_idx = selected_item_idx;
+        if let SubmenuItems::Source {
+            source,
+            selected_item,
+        } = &mut self.items
+        {
+            *selected_item = Self::create_source_item(&**source, selected_item_idx);
+        }
+    }
+
+    fn enter(&mut self) -> PressResult {
+        if let Some(on_enter) = &mut self.on_enter {
+            on_enter();
+        }
+        if matches!(self.selection_policy, SelectionPolicy::Reset) && self.selected_item_idx != 0 {
+            self.select(0);
+        }
+        self.refresh();
+        PressResult {
+            handled: true,
+            focus: true,
+        }
+    }
+
+    fn exit(&mut self) -> PressResult {
+        if let Some(on_exit) = &mut self.on_exit {
+            on_exit();
+        }
+        PressResult {
+            focus: false,
+            handled: true,
+        }
+    }
+
+    // Refreshes the source and keeps the selection within the new items
+    fn refresh(&mut self) {
+        if let SubmenuItems::Source { source, .. } = &mut self.items {
+            source.refresh();
+            let last_item_idx = source.count().saturating_sub(1);
+            self.select(self.selected_item_idx.min(last_item_idx));
+        }
     }
 
     fn up(&mut self) -> bool {
         if self.is_focused {
             false
         } else if let Some(new_selected_item_idx) = self.selected_item_idx.checked_sub(1) {
-            self.selected_item_idx = new_selected_item_idx;
+            self.select(new_selected_item_idx);
             true
         } else {
             false
@@ -52,8 +202,8 @@ impl SubmenuMenuItem {
         if self.is_focused {
             false
         } else if let Some(new_selected_item_idx) = self.selected_item_idx.checked_add(1) {
-            if new_selected_item_idx < self.items.len() {
-                self.selected_item_idx = new_selected_item_idx;
+            if new_selected_item_idx < self.item_count() {
+                self.select(new_selected_item_idx);
                 true
             } else {
                 false
@@ -73,18 +223,84 @@ impl SubmenuMenuItem {
         self.selected_item_idx
     }
 
+    pub fn set_selected_item_idx(&mut self, selected_item_idx: usize) -> Result<(), &'static str> {
+        if selected_item_idx >= self.item_count() {
+            Err("Selected item idx must be between 0 and items.len()")
+        } else {
+            self.select(selected_item_idx);
+            Ok(())
+        }
+    }
+
+    // None if there is no item at idx. Items of a source only exist while they are selected,
+    // so for a source this is None for every other row as well. get_item_label and
+    // write_item_label work for all rows.
     pub fn get_item(&self, idx: usize) -> Option<&dyn MenuItem> {
-        self.items.get(idx).map(|v| &**v)
+        match &self.items {
+            SubmenuItems::Static(items) => items.get(idx).map(|v| &**v),
+            SubmenuItems::Source { selected_item, .. } => {
+                if idx == self.selected_item_idx {
+                    Some(&**selected_item)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn get_item_label(&self, idx: usize, is_focused: bool) -> Option<String> {
+        if idx < self.item_count() {
+            let mut label_str: String = String::new();
+            self.write_item_label(&mut label_str, idx, is_focused)
+                .unwrap();
+            Some(label_str)
+        } else {
+            None
+        }
     }
 
+    // Fails if there is no item at idx
+    pub fn write_item_label(
+        &self,
+        writer: &mut dyn Write,
+        idx: usize,
+        is_focused: bool,
+    ) -> core::fmt::Result {
+        match &self.items {
+            SubmenuItems::Source { source, .. } if idx != self.selected_item_idx => {
+                if idx < source.count() {
+                    source.write_label(writer, idx)
+                } else {
+                    Err(core::fmt::Error)
+                }
+            }
+            _ => match self.get_item(idx) {
+                Some(item) => item.write_label(writer, is_focused),
+                None => Err(core::fmt::Error),
+            },
+        }
+    }
+
+    // An empty source is displayed as a single "-" item
     pub fn item_count(&self) -> usize {
-        self.items.len()
+        match &self.items {
+            SubmenuItems::Static(items) => items.len(),
+            SubmenuItems::Source { source, .. } => source.count().max(1),
+        }
     }
 }
 
 impl MenuItem for SubmenuMenuItem {
-    fn get_label(&self, _is_focused: bool) -> String {
-        self.label.clone()
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        writer.write_str(&self.label)
+    }
+
+    fn get_kind(&self, _is_focused: bool) -> ItemKind {
+        ItemKind::Submenu
+    }
+
+    fn get_child_submenu(&self) -> Option<&SubmenuMenuItem> {
+        Some(self)
     }
 
     fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult {
@@ -103,10 +319,7 @@ impl MenuItem for SubmenuMenuItem {
             } else {
                 if let Some(function_key) = &key.function_key {
                     match function_key {
-                        FunctionKey::BACK => PressResult {
-                            focus: false,
-                            handled: true,
-                        },
+                        FunctionKey::BACK => self.exit(),
                         FunctionKey::UP => {
                             let handled = self.up();
                             PressResult {
@@ -135,10 +348,7 @@ impl MenuItem for SubmenuMenuItem {
             }
         } else {
             match key.function_key {
-                Some(FunctionKey::ENTER) => PressResult {
-                    handled: true,
-                    focus: true,
-                },
+                Some(FunctionKey::ENTER) => self.enter(),
                 _ => PressResult {
                     handled: false,
                     focus: false,
@@ -147,6 +357,20 @@ impl MenuItem for SubmenuMenuItem {
         }
     }
 
+    fn update(&mut self) -> bool {
+        match &mut self.items {
+            SubmenuItems::Static(items) => {
+                // Every item has to be updated, so no short circuiting
+                let mut changed = false;
+                for item in items.iter_mut() {
+                    changed |= item.update();
+                }
+                changed
+            }
+            SubmenuItems::Source { selected_item, .. } => selected_item.update(),
+        }
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -161,6 +385,9 @@ mod tests {
     use super::*;
     use crate::menu_items::basic_menu_item::BasicMenuItem;
 
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     #[test]
     fn can_create_a_menu_item() {
         let items: Vec<Box<dyn MenuItem>> =
@@ -169,4 +396,115 @@ mod tests {
 
         assert_eq!(item.get_label(false), "label");
     }
+
+    struct RecordSource {
+        record_count: usize,
+    }
+
+    impl SubmenuSource for RecordSource {
+        fn count(&self) -> usize {
+            self.record_count
+        }
+
+        fn get_label(&self, idx: usize) -> String {
+            format!("Record {}", idx)
+        }
+
+        fn create_item(&self, idx: usize) -> Box<dyn MenuItem> {
+            Box::new(BasicMenuItem::new(format!("Record {} selected", idx)))
+        }
+
+        fn refresh(&mut self) {
+            self.record_count -= 1;
+        }
+    }
+
+    #[test]
+    fn source_items_are_created_on_demand() {
+        let source = RecordSource {
+            record_count: 10_000,
+        };
+        let mut item = SubmenuMenuItem::new_with_source(String::from("Records"), Box::new(source));
+        assert_eq!(item.item_count(), 10_000);
+        assert_eq!(item.get_item_label(5_000, false).unwrap(), "Record 5000");
+        assert_eq!(item.get_item_label(0, false).unwrap(), "Record 0 selected");
+        assert!(item.get_item(0).is_some());
+        assert!(item.get_item(1).is_none());
+        assert!(item.get_item_label(10_000, false).is_none());
+
+        item.set_selected_item_idx(9_999).unwrap();
+        assert_eq!(
+            item.get_selected_item().get_label(false),
+            "Record 9999 selected"
+        );
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.item_count(), 9_999);
+        assert_eq!(item.get_selected_item_idx(), 9_998);
+        assert_eq!(
+            item.get_selected_item().get_label(false),
+            "Record 9998 selected"
+        );
+    }
+
+    #[test]
+    fn empty_source_shows_placeholder() {
+        let source = RecordSource { record_count: 1 };
+        let mut item = SubmenuMenuItem::new_with_source(String::from("Records"), Box::new(source));
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(item.item_count(), 1);
+        assert_eq!(item.get_item_label(0, false).unwrap(), "-");
+    }
+
+    #[test]
+    fn hooks_are_called() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+        let mut item = SubmenuMenuItem::new(String::from("Scan"), items).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let enter_events = Rc::clone(&events);
+        item.set_on_enter(Box::new(move || enter_events.borrow_mut().push("enter")));
+        let exit_events = Rc::clone(&events);
+        item.set_on_exit(Box::new(move || exit_events.borrow_mut().push("exit")));
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true);
+        assert_eq!(*events.borrow(), vec!["enter"]);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(*events.borrow(), vec!["enter", "exit"]);
+    }
+
+    #[test]
+    fn selection_follows_policy() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+        let mut item = SubmenuMenuItem::new(String::from("label"), items).unwrap();
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::DOWN), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(item.get_selected_item_idx(), 1);
+
+        item.set_selection_policy(SelectionPolicy::Reset);
+        item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert_eq!(item.get_selected_item_idx(), 0);
+    }
 }
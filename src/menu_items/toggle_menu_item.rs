@@ -1,13 +1,24 @@
 use crate::keyboard::{FunctionKey, KeyboardKey};
-use crate::menu_items::menu_item::{MenuItem, PressResult};
+use crate::menu_items::binding::Binding;
+use crate::menu_items::menu_item::{ItemKind, MenuItem, PressResult};
 use core::fmt::Write;
 use std::any::Any;
 
+pub enum ToggleMode {
+    // ENTER flips the value
+    Immediate,
+    // ENTER focuses the item, LEFT/RIGHT flip the value, ENTER confirms and BACK cancels
+    Staged,
+}
+
 pub struct ToggleMenuItem {
     label: String,
     text_true: String,
     text_false: String,
     value: bool,
+    focused_value: bool,
+    mode: ToggleMode,
+    binding: Option<Box<dyn Binding<bool>>>,
 }
 
 impl ToggleMenuItem {
@@ -17,43 +28,133 @@ impl ToggleMenuItem {
             text_true: String::from("ON"),
             text_false: String::from("OFF"),
             value: false,
+            focused_value: false,
+            mode: ToggleMode::Immediate,
+            binding: None,
         }
     }
 
     pub fn get_value(&self) -> bool {
-        self.value
+        match &self.binding {
+            Some(binding) => binding.read(),
+            None => self.value,
+        }
     }
 
-    fn enter(&mut self, _is_focused: bool) -> bool {
-        self.value = !self.value;
-        true
+    pub fn set_value(&mut self, value: bool) {
+        self.value = value;
+        self.focused_value = value;
+        self.store_value();
+    }
+
+    pub fn set_binding(&mut self, binding: Box<dyn Binding<bool>>) {
+        self.binding = Some(binding);
+        self.load_value();
+    }
+
+    fn load_value(&mut self) {
+        self.value = self.get_value();
+        self.focused_value = self.value;
+    }
+
+    fn store_value(&mut self) {
+        if let Some(binding) = &mut self.binding {
+            binding.write(self.value);
+        }
+    }
+
+    pub fn set_text_true(&mut self, text_true: String) {
+        self.text_true = text_true;
+    }
+
+    pub fn set_text_false(&mut self, text_false: String) {
+        self.text_false = text_false;
+    }
+
+    pub fn set_mode(&mut self, mode: ToggleMode) {
+        self.mode = mode;
+    }
+
+    fn enter(&mut self, is_focused: bool) -> PressResult {
+        match self.mode {
+            ToggleMode::Immediate => {
+                self.load_value();
+                self.set_value(!self.value);
+                PressResult {
+                    handled: true,
+                    focus: false,
+                }
+            }
+            ToggleMode::Staged => {
+                if is_focused {
+                    self.value = self.focused_value;
+                    self.store_value();
+                } else {
+                    self.load_value();
+                }
+                PressResult {
+                    handled: true,
+                    focus: !is_focused,
+                }
+            }
+        }
+    }
+
+    fn back(&mut self) -> PressResult {
+        self.focused_value = self.value;
+        PressResult {
+            handled: true,
+            focus: false,
+        }
+    }
+
+    fn flip_focused_value(&mut self) -> PressResult {
+        self.focused_value = !self.focused_value;
+        PressResult {
+            handled: true,
+            focus: true,
+        }
     }
 }
 
 impl MenuItem for ToggleMenuItem {
-    fn get_label(&self, _is_focused: bool) -> String {
-        let value_text = if self.value {
+    fn write_label(&self, writer: &mut dyn Write, is_focused: bool) -> core::fmt::Result {
+        let value = if is_focused {
+            self.focused_value
+        } else {
+            self.get_value()
+        };
+        let value_text = if value {
             &self.text_true
         } else {
             &self.text_false
         };
-        let mut label_str: String = String::new();
-        write!(label_str, "{}: {}", self.label, &value_text).unwrap();
-        label_str
+        write!(writer, "{}: {}", self.label, value_text)
+    }
+
+    fn write_name(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        writer.write_str(&self.label)
+    }
+
+    fn get_kind(&self, is_focused: bool) -> ItemKind {
+        if is_focused {
+            ItemKind::Toggle(self.focused_value)
+        } else {
+            ItemKind::Toggle(self.get_value())
+        }
     }
 
     fn press(&mut self, key: &KeyboardKey, is_focused: bool) -> PressResult {
-        let handled = if let Some(function_key) = &key.function_key {
-            match function_key {
-                FunctionKey::ENTER => self.enter(is_focused),
-                _ => false,
+        match &key.function_key {
+            Some(FunctionKey::ENTER) => self.enter(is_focused),
+            Some(FunctionKey::BACK) if is_focused => self.back(),
+            Some(FunctionKey::LEFT) | Some(FunctionKey::RIGHT) if is_focused => {
+                self.flip_focused_value()
             }
-        } else {
-            false
-        };
-        PressResult {
-            handled,
-            focus: false,
+            _ => PressResult {
+                handled: false,
+                focus: is_focused,
+            },
         }
     }
 
@@ -70,6 +171,9 @@ impl MenuItem for ToggleMenuItem {
 mod tests {
     use super::*;
 
+    use std::cell::Cell;
+    use std::rc::Rc;
+
     #[test]
     fn item_is_usable() {
         let mut item: ToggleMenuItem = ToggleMenuItem::new(String::from("label"));
@@ -96,7 +200,7 @@ mod tests {
         );
 
         assert_eq!(item.get_label(false), "label: OFF");
-        assert_eq!(item.get_value(), false);
+        assert!(!item.get_value());
 
         assert_eq!(
             item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
@@ -106,7 +210,7 @@ mod tests {
             }
         );
         assert_eq!(item.get_label(false), "label: ON");
-        assert_eq!(item.get_value(), true);
+        assert!(item.get_value());
 
         assert_eq!(
             item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
@@ -116,6 +220,84 @@ mod tests {
             }
         );
         assert_eq!(item.get_label(false), "label: OFF");
-        assert_eq!(item.get_value(), false);
+        assert!(!item.get_value());
+    }
+
+    #[test]
+    fn staged_item_is_usable() {
+        let mut item: ToggleMenuItem = ToggleMenuItem::new(String::from("Heater"));
+        item.set_mode(ToggleMode::Staged);
+        item.set_text_true(String::from("yes"));
+        item.set_text_false(String::from("no"));
+        assert_eq!(item.get_label(false), "Heater: no");
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::RIGHT), None), true),
+            PressResult {
+                focus: true,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(true), "Heater: yes");
+        assert_eq!(item.get_label(false), "Heater: no");
+        assert!(!item.get_value());
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::BACK), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(true), "Heater: no");
+        assert!(!item.get_value());
+
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true);
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::UP), None), true),
+            PressResult {
+                focus: true,
+                handled: false
+            }
+        );
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true),
+            PressResult {
+                focus: false,
+                handled: true
+            }
+        );
+        assert_eq!(item.get_label(false), "Heater: yes");
+        assert!(item.get_value());
+    }
+
+    #[test]
+    fn bound_value_is_read_and_written() {
+        let heater = Rc::new(Cell::new(true));
+        let mut item: ToggleMenuItem = ToggleMenuItem::new(String::from("Heater"));
+        item.set_binding(Box::new(Rc::clone(&heater)));
+        assert_eq!(item.get_label(false), "Heater: ON");
+
+        heater.set(false);
+        assert_eq!(item.get_label(false), "Heater: OFF");
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        assert!(heater.get());
+
+        item.set_mode(ToggleMode::Staged);
+        heater.set(false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false);
+        item.press(&KeyboardKey::new(Some(FunctionKey::LEFT), None), true);
+        assert_eq!(item.get_label(true), "Heater: ON");
+        assert!(!heater.get());
+        item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), true);
+        assert!(heater.get());
     }
 }
@@ -0,0 +1,134 @@
+use crate::keyboard::KeyboardKey;
+use crate::menu_items::menu_item::{MenuItem, PressResult};
+use core::fmt::{Display, Write};
+use std::any::Any;
+
+type Formatter<T> = Box<dyn Fn(&T) -> String>;
+
+// Read only item displaying a value queried from the provider on every render
+pub struct ValueMenuItem<T: Display + PartialEq + 'static = String> {
+    label: String,
+    provider: Box<dyn Fn() -> T>,
+    unit: String,
+    formatter: Option<Formatter<T>>,
+    last_value: Option<T>,
+}
+
+impl<T: Display + PartialEq + 'static> ValueMenuItem<T> {
+    pub fn new(label: String, provider: Box<dyn Fn() -> T>) -> ValueMenuItem<T> {
+        ValueMenuItem {
+            label,
+            provider,
+            unit: String::new(),
+            formatter: None,
+            last_value: None,
+        }
+    }
+
+    pub fn get_value(&self) -> T {
+        (self.provider)()
+    }
+
+    // Appended as is, e.g. " °C" results in "Temp: 21 °C"
+    pub fn set_unit(&mut self, unit: String) {
+        self.unit = unit;
+    }
+
+    pub fn set_formatter(&mut self, formatter: Formatter<T>) {
+        self.formatter = Some(formatter);
+    }
+}
+
+impl<T: Display + PartialEq + 'static> MenuItem for ValueMenuItem<T> {
+    fn write_label(&self, writer: &mut dyn Write, _is_focused: bool) -> core::fmt::Result {
+        let value = self.get_value();
+        write!(writer, "{}: ", self.label)?;
+        if let Some(formatter) = &self.formatter {
+            writer.write_str(&formatter(&value))?;
+        } else {
+            write!(writer, "{}", value)?;
+        }
+        writer.write_str(&self.unit)
+    }
+
+    fn press(&mut self, _key: &KeyboardKey, _is_focused: bool) -> PressResult {
+        PressResult {
+            handled: false,
+            focus: false,
+        }
+    }
+
+    fn update(&mut self) -> bool {
+        let value = self.get_value();
+        let changed = self.last_value.as_ref() != Some(&value);
+        self.last_value = Some(value);
+        changed
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::FunctionKey;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn item_displays_current_value() {
+        let temperature = Rc::new(Cell::new(21));
+        let temperature_clone = Rc::clone(&temperature);
+        let mut item: ValueMenuItem<i32> = ValueMenuItem::new(
+            String::from("Temp"),
+            Box::new(move || temperature_clone.get()),
+        );
+        item.set_unit(String::from(" °C"));
+        assert_eq!(item.get_label(false), "Temp: 21 °C");
+
+        temperature.set(-3);
+        assert_eq!(item.get_label(false), "Temp: -3 °C");
+        assert_eq!(item.get_value(), -3);
+
+        assert_eq!(
+            item.press(&KeyboardKey::new(Some(FunctionKey::ENTER), None), false),
+            PressResult {
+                focus: false,
+                handled: false
+            }
+        );
+    }
+
+    #[test]
+    fn formatter_is_used() {
+        let mut item: ValueMenuItem<u32> =
+            ValueMenuItem::new(String::from("Uptime"), Box::new(|| 3725));
+        item.set_formatter(Box::new(|seconds| {
+            format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60)
+        }));
+        assert_eq!(item.get_label(false), "Uptime: 1h02m");
+    }
+
+    #[test]
+    fn update_reports_changes() {
+        let temperature = Rc::new(Cell::new(21));
+        let temperature_clone = Rc::clone(&temperature);
+        let mut item: ValueMenuItem<i32> = ValueMenuItem::new(
+            String::from("Temp"),
+            Box::new(move || temperature_clone.get()),
+        );
+
+        assert!(item.update());
+        assert!(!item.update());
+        temperature.set(22);
+        assert!(item.update());
+        assert!(!item.update());
+    }
+}
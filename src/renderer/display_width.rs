@@ -0,0 +1,151 @@
+use core::fmt::Write;
+
+// Chars taking no column, e.g. control chars, combining marks, zero width spaces and
+// variation selectors. 0x08-0x0F take one, they are the custom glyph codes of character
+// displays.
+const ZERO_WIDTH_RANGES: [(u32, u32); 11] = [
+    (0x0000, 0x0007),
+    (0x0010, 0x001F),
+    (0x007F, 0x009F),
+    (0x0300, 0x036F),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xE0100, 0xE01EF),
+];
+
+// East Asian wide and fullwidth chars taking two columns
+const WIDE_RANGES: [(u32, u32); 15] = [
+    (0x1100, 0x115F),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x1F64F),
+    (0x1F900, 0x1F9FF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+fn is_in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let code_point = c as u32;
+    ranges
+        .iter()
+        .any(|(first, last)| code_point >= *first && code_point <= *last)
+}
+
+// Without East Asian width every visible char takes one column, as on character LCDs
+pub fn char_width(c: char, east_asian_width: bool) -> usize {
+    if is_in_ranges(c, &ZERO_WIDTH_RANGES) {
+        0
+    } else if east_asian_width && is_in_ranges(c, &WIDE_RANGES) {
+        2
+    } else {
+        1
+    }
+}
+
+pub fn str_width(s: &str, east_asian_width: bool) -> usize {
+    s.chars().map(|c| char_width(c, east_asian_width)).sum()
+}
+
+// Longest prefix fitting in max_width columns and its width. A wide char which doesn't fit
+// completely is left out.
+pub fn truncate_to_width(s: &str, max_width: usize, east_asian_width: bool) -> (&str, usize) {
+    let mut width = 0;
+    for (byte_idx, c) in s.char_indices() {
+        let new_width = width + char_width(c, east_asian_width);
+        if new_width > max_width {
+            return (&s[..byte_idx], width);
+        }
+        width = new_width;
+    }
+    (s, width)
+}
+
+// Suffix left after skipping max_width columns and the columns skipped too much, as a wide
+// char can't be cut in half
+pub fn skip_width(s: &str, max_width: usize, east_asian_width: bool) -> (&str, usize) {
+    let mut width = 0;
+    for (byte_idx, c) in s.char_indices() {
+        let c_width = char_width(c, east_asian_width);
+        // Combining marks stay with the skipped char
+        if width >= max_width && c_width != 0 {
+            return (&s[byte_idx..], width - max_width);
+        }
+        width += c_width;
+    }
+    ("", width.saturating_sub(max_width))
+}
+
+// Display width of everything written, without storing it
+pub(crate) struct WidthCounter {
+    pub(crate) width: usize,
+    east_asian_width: bool,
+}
+
+impl WidthCounter {
+    pub(crate) fn new(east_asian_width: bool) -> Self {
+        WidthCounter {
+            width: 0,
+            east_asian_width,
+        }
+    }
+}
+
+impl Write for WidthCounter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.width += str_width(s, self.east_asian_width);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width_is_counted_in_columns() {
+        assert_eq!(str_width("Größe", false), 5);
+        assert_eq!(str_width("Température", false), 11);
+        assert_eq!(str_width("Tempe\u{301}rature", false), 11);
+        assert_eq!(str_width("→↑↓", true), 3);
+        assert_eq!(str_width("温度", false), 2);
+        assert_eq!(str_width("温度", true), 4);
+        assert_eq!(str_width("ｶﾀｶﾅ", true), 4);
+        assert_eq!(str_width("\u{8} Wifi\u{7}", false), 6);
+        assert_eq!(str_width("葛\u{E0100}飾", true), 4);
+        assert_eq!(str_width("☺\u{FE0F}", true), 1);
+        assert_eq!(
+            truncate_to_width("葛\u{E0100}飾", 2, true),
+            ("葛\u{E0100}", 2)
+        );
+    }
+
+    #[test]
+    fn truncation_respects_char_boundaries() {
+        assert_eq!(truncate_to_width("Größe", 3, false), ("Grö", 3));
+        assert_eq!(truncate_to_width("Größe", 10, false), ("Größe", 5));
+        assert_eq!(truncate_to_width("温度计", 3, true), ("温", 2));
+        assert_eq!(truncate_to_width("温度计", 4, true), ("温度", 4));
+        assert_eq!(truncate_to_width("e\u{301}", 1, false), ("e\u{301}", 1));
+    }
+
+    #[test]
+    fn skipping_respects_char_boundaries() {
+        assert_eq!(skip_width("Größe", 2, false), ("öße", 0));
+        assert_eq!(skip_width("温度计", 1, true), ("度计", 1));
+        assert_eq!(skip_width("温度计", 2, true), ("度计", 0));
+        assert_eq!(skip_width("ab", 5, false), ("", 0));
+        assert_eq!(skip_width("e\u{301}x", 1, false), ("x", 0));
+    }
+}
@@ -0,0 +1,316 @@
+use crate::renderer::display_width::char_width;
+use core::fmt::Write;
+
+// Fills the second cell of an East Asian wide char
+pub const WIDE_CHAR_CONTINUATION: char = '\0';
+
+// Fixed size grid of display cells which can be rendered into repeatedly without allocating
+pub struct FrameBuffer<const W: usize, const H: usize> {
+    cells: [[char; W]; H],
+}
+
+impl<const W: usize, const H: usize> FrameBuffer<W, H> {
+    pub fn new() -> FrameBuffer<W, H> {
+        FrameBuffer {
+            cells: [[' '; W]; H],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for line in self.cells.iter_mut() {
+            line.fill(' ');
+        }
+    }
+
+    pub fn get_lines(&self) -> &[[char; W]; H] {
+        &self.cells
+    }
+
+    pub fn get_line(&self, line_idx: usize) -> Option<&[char; W]> {
+        self.cells.get(line_idx)
+    }
+
+    pub(crate) fn get_line_mut(&mut self, line_idx: usize) -> &mut [char; W] {
+        &mut self.cells[line_idx]
+    }
+
+    // Allocates, meant for tests and debugging
+    pub fn line_to_string(&self, line_idx: usize) -> Option<String> {
+        self.get_line(line_idx).map(|line| {
+            line.iter()
+                .filter(|c| **c != WIDE_CHAR_CONTINUATION)
+                .collect()
+        })
+    }
+}
+
+impl<const W: usize, const H: usize> FrameBuffer<W, H> {
+    // Spans of cells which changed since the previous frame, so a slow display only rewrites
+    // those. Up to merge_gap unchanged cells between two changes are rewritten instead of
+    // starting a new span, as moving the cursor has a cost too.
+    pub fn diff<'a>(
+        &'a self,
+        previous: &'a FrameBuffer<W, H>,
+        merge_gap: usize,
+    ) -> FrameDiff<'a, W, H> {
+        FrameDiff {
+            frame: self,
+            previous,
+            merge_gap,
+            line_idx: 0,
+            column: 0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DiffSpan<'a> {
+    pub line_idx: usize,
+    pub column: usize,
+    // May contain wide char continuation cells, see chars
+    pub cells: &'a [char],
+}
+
+impl DiffSpan<'_> {
+    // The chars to write starting at the span column
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.cells
+            .iter()
+            .copied()
+            .filter(|c| *c != WIDE_CHAR_CONTINUATION)
+    }
+}
+
+pub struct FrameDiff<'a, const W: usize, const H: usize> {
+    frame: &'a FrameBuffer<W, H>,
+    previous: &'a FrameBuffer<W, H>,
+    merge_gap: usize,
+    line_idx: usize,
+    column: usize,
+}
+
+impl<'a, const W: usize, const H: usize> Iterator for FrameDiff<'a, W, H> {
+    type Item = DiffSpan<'a>;
+
+    fn next(&mut self) -> Option<DiffSpan<'a>> {
+        while self.line_idx < H {
+            let line = &self.frame.cells[self.line_idx];
+            let previous_line = &self.previous.cells[self.line_idx];
+            let first_changed_column =
+                (self.column..W).find(|column| line[*column] != previous_line[*column]);
+            let mut start = match first_changed_column {
+                Some(start) => start,
+                None => {
+                    self.line_idx += 1;
+                    self.column = 0;
+                    continue;
+                }
+            };
+
+            let mut end = start + 1;
+            for column in (start + 1)..W {
+                if line[column] != previous_line[column] {
+                    end = column + 1;
+                } else if column + 1 - end > self.merge_gap {
+                    break;
+                }
+            }
+            // Wide chars are always written whole
+            if start > 0 && line[start] == WIDE_CHAR_CONTINUATION {
+                start -= 1;
+            }
+            if end < W && line[end] == WIDE_CHAR_CONTINUATION {
+                end += 1;
+            }
+
+            self.column = end;
+            return Some(DiffSpan {
+                line_idx: self.line_idx,
+                column: start,
+                cells: &line[start..end],
+            });
+        }
+        None
+    }
+}
+
+impl<const W: usize, const H: usize> Default for FrameBuffer<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Writes a label into a line of cells, skipping the first columns for the marquee and
+// dropping everything which doesn't fit. Zero width chars can't be placed in a cell and
+// are dropped too.
+pub(crate) struct CellWriter<'a> {
+    cells: &'a mut [char],
+    column: usize,
+    skip_columns: usize,
+    // Of everything written, including skipped and truncated chars
+    width: usize,
+    east_asian_width: bool,
+}
+
+impl<'a> CellWriter<'a> {
+    pub(crate) fn new(cells: &'a mut [char], skip_columns: usize, east_asian_width: bool) -> Self {
+        CellWriter {
+            cells,
+            column: 0,
+            skip_columns,
+            width: 0,
+            east_asian_width,
+        }
+    }
+
+    pub(crate) fn get_column(&self) -> usize {
+        self.column
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.width
+    }
+}
+
+impl Write for CellWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            let c_width = char_width(c, self.east_asian_width);
+            if c_width == 0 {
+                continue;
+            }
+            self.width += c_width;
+            if self.skip_columns > 0 {
+                // Half of a skipped wide char is left blank
+                for _column_idx in self.skip_columns..c_width {
+                    self.put(' ', 1);
+                }
+                self.skip_columns = self.skip_columns.saturating_sub(c_width);
+            } else {
+                self.put(c, c_width);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CellWriter<'_> {
+    fn put(&mut self, c: char, c_width: usize) {
+        if self.column + c_width > self.cells.len() {
+            // Nothing is written after a char which doesn't fit
+            self.column = self.cells.len();
+            return;
+        }
+        self.cells[self.column] = c;
+        if c_width == 2 {
+            self.cells[self.column + 1] = WIDE_CHAR_CONTINUATION;
+        }
+        self.column += c_width;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_writer_truncates_and_skips() {
+        let mut cells = [' '; 5];
+        write!(CellWriter::new(&mut cells, 0, false), "Größe{}", 123).unwrap();
+        assert_eq!(cells, ['G', 'r', 'ö', 'ß', 'e']);
+
+        let mut cells = [' '; 5];
+        write!(CellWriter::new(&mut cells, 1, true), "温度a").unwrap();
+        assert_eq!(cells, [' ', '度', WIDE_CHAR_CONTINUATION, 'a', ' ']);
+
+        let mut cells = [' '; 3];
+        write!(CellWriter::new(&mut cells, 0, true), "a温度").unwrap();
+        assert_eq!(cells, ['a', '温', WIDE_CHAR_CONTINUATION]);
+        let mut cells = [' '; 4];
+        write!(CellWriter::new(&mut cells, 0, true), "ab温a").unwrap();
+        assert_eq!(cells, ['a', 'b', '温', WIDE_CHAR_CONTINUATION]);
+        let mut cells = [' '; 3];
+        let mut writer = CellWriter::new(&mut cells, 0, true);
+        write!(writer, "ab温a").unwrap();
+        assert_eq!((writer.get_column(), writer.get_width()), (3, 5));
+        assert_eq!(cells, ['a', 'b', ' ']);
+    }
+
+    #[test]
+    fn frame_buffer_lines_are_readable() {
+        let mut frame: FrameBuffer<3, 2> = FrameBuffer::new();
+        frame.get_line_mut(1)[0] = '温';
+        frame.get_line_mut(1)[1] = WIDE_CHAR_CONTINUATION;
+        assert_eq!(frame.line_to_string(0).unwrap(), "   ");
+        assert_eq!(frame.line_to_string(1).unwrap(), "温 ");
+        assert!(frame.line_to_string(2).is_none());
+        frame.clear();
+        assert_eq!(frame.get_lines(), &[[' '; 3]; 2]);
+    }
+
+    fn create_frame<const W: usize, const H: usize>(lines: [&str; H]) -> FrameBuffer<W, H> {
+        let mut frame = FrameBuffer::new();
+        for (line_idx, line) in lines.iter().enumerate() {
+            write!(
+                CellWriter::new(frame.get_line_mut(line_idx), 0, true),
+                "{}",
+                line
+            )
+            .unwrap();
+        }
+        frame
+    }
+
+    fn diff_to_strings<const W: usize, const H: usize>(
+        frame: &FrameBuffer<W, H>,
+        previous: &FrameBuffer<W, H>,
+        merge_gap: usize,
+    ) -> Vec<(usize, usize, String)> {
+        frame
+            .diff(previous, merge_gap)
+            .map(|span| (span.line_idx, span.column, span.chars().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn diff_yields_changed_spans() {
+        let previous: FrameBuffer<8, 2> = create_frame(["→Item1 ↓", " Item2  "]);
+        assert!(previous.diff(&previous, 0).next().is_none());
+
+        let frame = create_frame([" Item1 ↓", "→Item2  "]);
+        assert_eq!(
+            diff_to_strings(&frame, &previous, 0),
+            vec![(0, 0, String::from(" ")), (1, 0, String::from("→"))]
+        );
+
+        let frame = create_frame(["→Item3 ↑", " Item2  "]);
+        assert_eq!(
+            diff_to_strings(&frame, &previous, 0),
+            vec![(0, 5, String::from("3")), (0, 7, String::from("↑"))]
+        );
+        assert_eq!(
+            diff_to_strings(&frame, &previous, 1),
+            vec![(0, 5, String::from("3 ↑"))]
+        );
+    }
+
+    #[test]
+    fn diff_keeps_wide_chars_whole() {
+        let previous: FrameBuffer<6, 1> = create_frame(["→温度 "]);
+        let frame = create_frame(["→温x  "]);
+        assert_eq!(
+            diff_to_strings(&frame, &previous, 0),
+            vec![(0, 3, String::from("x "))]
+        );
+        let frame = create_frame(["→a温  "]);
+        assert_eq!(
+            diff_to_strings(&frame, &previous, 0),
+            vec![(0, 1, String::from("a温 "))]
+        );
+        let frame = create_frame(["→温设 "]);
+        assert_eq!(
+            diff_to_strings(&frame, &previous, 0),
+            vec![(0, 3, String::from("设"))]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::renderer::display_width::char_width;
+use crate::renderer::frame_buffer::FrameBuffer;
+
+pub const CGRAM_SLOT_COUNT: usize = 8;
+// CGRAM is mirrored at 0x08-0x0F, which keeps the glyphs apart from the wide char continuation
+const FIRST_GLYPH_CODE: u8 = 0x08;
+
+// 5x8 pixels, one row per byte with the 5 lowest bits set for lit pixels
+pub type GlyphBitmap = [u8; 8];
+
+pub const CHECKED_BOX: GlyphBitmap = [0x00, 0x1F, 0x11, 0x1B, 0x15, 0x1B, 0x11, 0x1F];
+pub const UNCHECKED_BOX: GlyphBitmap = [0x00, 0x1F, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1F];
+pub const LOCK: GlyphBitmap = [0x0E, 0x11, 0x11, 0x1F, 0x1B, 0x1B, 0x1F, 0x00];
+pub const BATTERY: GlyphBitmap = [0x0E, 0x1B, 0x11, 0x11, 0x1F, 0x1F, 0x1F, 0x00];
+pub const ARROW_UP: GlyphBitmap = [0x04, 0x0E, 0x15, 0x04, 0x04, 0x04, 0x04, 0x00];
+pub const ARROW_DOWN: GlyphBitmap = [0x04, 0x04, 0x04, 0x04, 0x15, 0x0E, 0x04, 0x00];
+
+#[derive(Debug, PartialEq)]
+pub struct GlyphUpload {
+    pub slot: usize,
+    pub bitmap: GlyphBitmap,
+}
+
+// Custom glyphs of character displays. Chars with a registered glyph are replaced by the
+// code of the CGRAM slot holding it, slots are assigned per frame and kept while in use.
+pub struct GlyphRegistry {
+    glyphs: Vec<(char, GlyphBitmap)>,
+    slots: [Option<char>; CGRAM_SLOT_COUNT],
+}
+
+impl GlyphRegistry {
+    pub fn new() -> GlyphRegistry {
+        GlyphRegistry {
+            glyphs: Vec::new(),
+            slots: [None; CGRAM_SLOT_COUNT],
+        }
+    }
+
+    // The glyph takes one cell, so c must be one column wide with East Asian width as well
+    pub fn register(&mut self, c: char, bitmap: GlyphBitmap) -> Result<(), &'static str> {
+        if bitmap.iter().any(|row| *row > 0x1F) {
+            return Err("Glyph rows can't be wider than 5 pixels");
+        }
+        if char_width(c, true) != 1 {
+            return Err("Glyphs can only replace chars one column wide");
+        }
+        self.glyphs.retain(|(glyph_c, _)| *glyph_c != c);
+        self.glyphs.push((c, bitmap));
+        // An outdated bitmap must be uploaded again
+        for slot in self.slots.iter_mut() {
+            if *slot == Some(c) {
+                *slot = None;
+            }
+        }
+        Ok(())
+    }
+
+    // Char code of the slot holding the glyph of c in the last substituted frame
+    pub fn get_glyph_code(&self, c: char) -> Option<char> {
+        self.slots
+            .iter()
+            .position(|slot| *slot == Some(c))
+            .map(|slot_idx| char::from(FIRST_GLYPH_CODE + slot_idx as u8))
+    }
+
+    // Replaces the glyph chars of the rendered lines and returns the bitmaps to upload
+    // before showing them. Nothing is changed if more glyphs are needed than slots exist.
+    pub fn substitute_lines(
+        &mut self,
+        lines: &mut [String],
+    ) -> Result<Vec<GlyphUpload>, &'static str> {
+        let uploads = self.assign_slots(lines.iter().flat_map(|line| line.chars()))?;
+        for line in lines.iter_mut() {
+            if line.chars().any(|c| self.get_glyph_code(c).is_some()) {
+                *line = line
+                    .chars()
+                    .map(|c| self.get_glyph_code(c).unwrap_or(c))
+                    .collect();
+            }
+        }
+        Ok(uploads)
+    }
+
+    pub fn substitute_frame<const W: usize, const H: usize>(
+        &mut self,
+        frame: &mut FrameBuffer<W, H>,
+    ) -> Result<Vec<GlyphUpload>, &'static str> {
+        let uploads = self.assign_slots(frame.get_lines().iter().flatten().copied())?;
+        for line_idx in 0..H {
+            for cell in frame.get_line_mut(line_idx).iter_mut() {
+                if let Some(glyph_code) = self.get_glyph_code(*cell) {
+                    *cell = glyph_code;
+                }
+            }
+        }
+        Ok(uploads)
+    }
+
+    fn get_bitmap(&self, c: char) -> Option<GlyphBitmap> {
+        self.glyphs
+            .iter()
+            .find(|(glyph_c, _)| *glyph_c == c)
+            .map(|(_, bitmap)| *bitmap)
+    }
+
+    fn assign_slots(
+        &mut self,
+        chars: impl Iterator<Item = char>,
+    ) -> Result<Vec<GlyphUpload>, &'static str> {
+        let mut used_chars: Vec<char> = Vec::new();
+        for c in chars {
+            if !used_chars.contains(&c) && self.get_bitmap(c).is_some() {
+                used_chars.push(c);
+            }
+        }
+        if used_chars.len() > CGRAM_SLOT_COUNT {
+            return Err("A frame can't use more than 8 custom glyphs");
+        }
+
+        let mut uploads = Vec::new();
+        for c in used_chars.iter().copied() {
+            if self.slots.contains(&Some(c)) {
+                continue;
+            }
+            // Empty slots first, glyphs not in this frame may be needed again soon
+            let slot_idx = self
+                .slots
+                .iter()
+                .position(|slot| slot.is_none())
+                .or_else(|| {
+                    self.slots
+                        .iter()
+                        .position(|slot| slot.is_some_and(|slot_c| !used_chars.contains(&slot_c)))
+                })
+                .unwrap();
+            self.slots[slot_idx] = Some(c);
+            uploads.push(GlyphUpload {
+                slot: slot_idx,
+                bitmap: self.get_bitmap(c).unwrap(),
+            });
+        }
+        Ok(uploads)
+    }
+}
+
+impl Default for GlyphRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::display_width::str_width;
+
+    #[test]
+    fn glyphs_are_assigned_to_slots() {
+        let mut registry = GlyphRegistry::new();
+        registry.register('☑', CHECKED_BOX).unwrap();
+        registry.register('☐', UNCHECKED_BOX).unwrap();
+        registry.register('⚿', LOCK).unwrap();
+        assert!(registry.register('x', [0x20; 8]).is_err());
+        assert!(registry.register('🔋', BATTERY).is_err());
+        assert!(registry.register('\u{301}', BATTERY).is_err());
+
+        let mut lines = vec![String::from("☑ Wifi"), String::from("☐ Bluetooth")];
+        let uploads = registry.substitute_lines(&mut lines).unwrap();
+        assert_eq!(
+            uploads,
+            vec![
+                GlyphUpload {
+                    slot: 0,
+                    bitmap: CHECKED_BOX
+                },
+                GlyphUpload {
+                    slot: 1,
+                    bitmap: UNCHECKED_BOX
+                },
+            ]
+        );
+        assert_eq!(lines, vec!["\u{8} Wifi", "\u{9} Bluetooth"]);
+
+        // Glyphs in use keep their slot
+        let mut lines = vec![String::from("⚿ Lock"), String::from("☐ Bluetooth")];
+        let uploads = registry.substitute_lines(&mut lines).unwrap();
+        assert_eq!(
+            uploads,
+            vec![GlyphUpload {
+                slot: 2,
+                bitmap: LOCK
+            }]
+        );
+        assert_eq!(lines, vec!["\u{a} Lock", "\u{9} Bluetooth"]);
+        assert_eq!(str_width(&lines[0], true), 6);
+        assert!(registry.substitute_lines(&mut lines).unwrap().is_empty());
+    }
+
+    #[test]
+    fn too_many_glyphs_fail() {
+        let mut registry = GlyphRegistry::new();
+        let glyph_chars = ['0', '1', '2', '3', '4', '5', '6', '7', '8'];
+        for c in glyph_chars {
+            registry.register(c, BATTERY).unwrap();
+        }
+
+        let mut lines = vec![String::from("01234567")];
+        assert_eq!(registry.substitute_lines(&mut lines).unwrap().len(), 8);
+
+        // Stale slots are reused
+        let mut lines = vec![String::from("8")];
+        let uploads = registry.substitute_lines(&mut lines).unwrap();
+        assert_eq!(uploads[0].slot, 0);
+
+        let mut lines = vec![String::from("012345678")];
+        assert!(registry.substitute_lines(&mut lines).is_err());
+        assert_eq!(lines, vec!["012345678"]);
+    }
+
+    #[test]
+    fn frames_are_substituted() {
+        let mut registry = GlyphRegistry::new();
+        registry.register('↑', ARROW_UP).unwrap();
+        registry.register('↓', ARROW_DOWN).unwrap();
+
+        let mut frame: FrameBuffer<3, 2> = FrameBuffer::new();
+        frame.get_line_mut(1)[2] = '↓';
+        assert_eq!(registry.substitute_frame(&mut frame).unwrap().len(), 1);
+        assert_eq!(frame.get_lines(), &[[' ', ' ', ' '], [' ', ' ', '\u{8}']]);
+        assert_eq!(registry.get_glyph_code('↓'), Some('\u{8}'));
+        assert_eq!(registry.get_glyph_code('↑'), None);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::renderer::frame_buffer::WIDE_CHAR_CONTINUATION;
+
+// Character ROM code pages of HD44780 compatible displays, see the HD44780U datasheet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CharacterRom {
+    // Japanese, with katakana
+    A00,
+    // European, with Latin-1 and arrows
+    A02,
+}
+
+const A00_CODES: [(char, u8); 25] = [
+    ('¥', 0x5C),
+    ('→', 0x7E),
+    ('←', 0x7F),
+    ('°', 0xDF),
+    ('α', 0xE0),
+    ('ä', 0xE1),
+    ('β', 0xE2),
+    ('ß', 0xE2),
+    ('ε', 0xE3),
+    ('μ', 0xE4),
+    ('σ', 0xE5),
+    ('ρ', 0xE6),
+    ('√', 0xE8),
+    ('¢', 0xEC),
+    ('£', 0xED),
+    ('ñ', 0xEE),
+    ('ö', 0xEF),
+    ('θ', 0xF2),
+    ('∞', 0xF3),
+    ('Ω', 0xF4),
+    ('ü', 0xF5),
+    ('Σ', 0xF6),
+    ('π', 0xF7),
+    ('÷', 0xFD),
+    ('█', 0xFF),
+];
+
+const A02_CODES: [(char, u8); 10] = [
+    ('▶', 0x10),
+    ('◀', 0x11),
+    ('↑', 0x18),
+    ('↓', 0x19),
+    ('→', 0x1A),
+    ('←', 0x1B),
+    ('≤', 0x1C),
+    ('≥', 0x1D),
+    ('▲', 0x1E),
+    ('▼', 0x1F),
+];
+
+#[derive(Debug, PartialEq)]
+pub struct EncodedText {
+    pub bytes: Vec<u8>,
+    // Chars written as the fallback code, in order of appearance
+    pub unmappable_chars: Vec<char>,
+}
+
+// Maps rendered text to the byte codes of a character ROM
+pub struct Hd44780Encoder {
+    rom: CharacterRom,
+    replacements: Vec<(char, u8)>,
+    fallback_code: u8,
+}
+
+impl Hd44780Encoder {
+    pub fn new(rom: CharacterRom) -> Hd44780Encoder {
+        Hd44780Encoder {
+            rom,
+            replacements: Vec::new(),
+            fallback_code: b'?',
+        }
+    }
+
+    // Takes precedence over the ROM, e.g. to map a char to a custom glyph
+    pub fn set_replacement(&mut self, c: char, code: u8) {
+        self.replacements.retain(|(replaced_c, _)| *replaced_c != c);
+        self.replacements.push((c, code));
+    }
+
+    pub fn set_fallback_code(&mut self, fallback_code: u8) {
+        self.fallback_code = fallback_code;
+    }
+
+    pub fn encode_char(&self, c: char) -> Option<u8> {
+        if let Some((_, code)) = self
+            .replacements
+            .iter()
+            .find(|(replaced_c, _)| *replaced_c == c)
+        {
+            return Some(*code);
+        }
+        // Custom glyphs, see GlyphRegistry
+        if ('\u{8}'..='\u{F}').contains(&c) {
+            return Some(c as u8);
+        }
+        match self.rom {
+            CharacterRom::A00 => encode_a00_char(c),
+            CharacterRom::A02 => encode_a02_char(c),
+        }
+    }
+
+    pub fn encode(&self, text: &str) -> EncodedText {
+        let mut encoded_text = EncodedText {
+            bytes: Vec::new(),
+            unmappable_chars: Vec::new(),
+        };
+        for c in text.chars().filter(|c| *c != WIDE_CHAR_CONTINUATION) {
+            match self.encode_char(c) {
+                Some(code) => encoded_text.bytes.push(code),
+                None => {
+                    encoded_text.bytes.push(self.fallback_code);
+                    encoded_text.unmappable_chars.push(c);
+                }
+            }
+        }
+        encoded_text
+    }
+}
+
+fn find_code(codes: &[(char, u8)], c: char) -> Option<u8> {
+    codes
+        .iter()
+        .find(|(code_c, _)| *code_c == c)
+        .map(|(_, code)| *code)
+}
+
+fn encode_a00_char(c: char) -> Option<u8> {
+    match c {
+        // The backslash and the tilde are replaced by the yen sign and the arrows
+        '\\' | '~' => None,
+        ' '..='}' => Some(c as u8),
+        // Halfwidth katakana and punctuation
+        '\u{FF61}'..='\u{FF9F}' => Some((c as u32 - 0xFF61 + 0xA1) as u8),
+        _ => find_code(&A00_CODES, c),
+    }
+}
+
+fn encode_a02_char(c: char) -> Option<u8> {
+    match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => Some(c as u8),
+        _ => find_code(&A02_CODES, c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chars_are_encoded_per_rom() {
+        let a00_encoder = Hd44780Encoder::new(CharacterRom::A00);
+        assert_eq!(a00_encoder.encode_char('A'), Some(0x41));
+        assert_eq!(a00_encoder.encode_char('\u{8}'), Some(0x08));
+        assert_eq!(a00_encoder.encode_char('→'), Some(0x7E));
+        assert_eq!(a00_encoder.encode_char('ｱ'), Some(0xB1));
+        assert_eq!(a00_encoder.encode_char('°'), Some(0xDF));
+        assert_eq!(a00_encoder.encode_char('\\'), None);
+        assert_eq!(a00_encoder.encode_char('↑'), None);
+        assert_eq!(a00_encoder.encode_char('é'), None);
+
+        let a02_encoder = Hd44780Encoder::new(CharacterRom::A02);
+        assert_eq!(a02_encoder.encode_char('~'), Some(0x7E));
+        assert_eq!(a02_encoder.encode_char('→'), Some(0x1A));
+        assert_eq!(a02_encoder.encode_char('↓'), Some(0x19));
+        assert_eq!(a02_encoder.encode_char('é'), Some(0xE9));
+        assert_eq!(a02_encoder.encode_char('ｱ'), None);
+    }
+
+    #[test]
+    fn unmappable_chars_are_reported() {
+        let mut encoder = Hd44780Encoder::new(CharacterRom::A00);
+        assert_eq!(
+            encoder.encode("→Café ↓"),
+            EncodedText {
+                bytes: vec![0x7E, b'C', b'a', b'f', b'?', b' ', b'?'],
+                unmappable_chars: vec!['é', '↓'],
+            }
+        );
+
+        encoder.set_replacement('é', b'e');
+        encoder.set_replacement('↓', 0x01);
+        encoder.set_replacement('↓', b'v');
+        encoder.set_fallback_code(b' ');
+        assert_eq!(
+            encoder.encode("→Café ↓\\"),
+            EncodedText {
+                bytes: vec![0x7E, b'C', b'a', b'f', b'e', b' ', b'v', b' '],
+                unmappable_chars: vec!['\\'],
+            }
+        );
+    }
+}
@@ -0,0 +1,36 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeaderTitle {
+    // Name of the innermost entered submenu
+    Submenu,
+    // Names of all entered submenus, cut off at the start if they don't fit
+    Breadcrumb,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeaderAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+// First line of the display, telling which submenu is shown
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Header {
+    pub title: HeaderTitle,
+    pub alignment: HeaderAlignment,
+    // Shown while no submenu is entered
+    pub root_title: &'static str,
+    // Position of the selected item, e.g. "3/12", right aligned
+    pub show_position: bool,
+}
+
+impl Header {
+    pub fn new(title: HeaderTitle, root_title: &'static str) -> Header {
+        Header {
+            title,
+            alignment: HeaderAlignment::Left,
+            root_title,
+            show_position: false,
+        }
+    }
+}
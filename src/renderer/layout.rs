@@ -0,0 +1,573 @@
+use crate::menu::Menu;
+use crate::menu_items::menu_item::{ItemKind, MenuItem, PickerView};
+use crate::menu_items::submenu_menu_item::SubmenuMenuItem;
+use core::cell::Cell;
+use core::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineSelection {
+    Unselected,
+    Selected,
+    // The selected item is being edited
+    Focused,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollIndicator {
+    None,
+    // More entries above the first line
+    Up,
+    // More entries below the last line
+    Down,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LayoutLine {
+    pub label: String,
+    pub selection: LineSelection,
+    pub scroll_indicator: ScrollIndicator,
+    // Char idx in the label of the position being edited
+    pub cursor_position: Option<usize>,
+    pub kind: ItemKind,
+    pub enabled: bool,
+    // Label without the value, only for toggles
+    pub name: Option<String>,
+}
+
+// Position of the selected entry in the displayed list, e.g. for a "3/12" indicator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ListPosition {
+    pub selected_entry_idx: usize,
+    pub entry_count: usize,
+}
+
+// Visible window of the menu, shared by all renderers. Lines are not truncated, fitting them
+// into the display is up to the renderer.
+#[derive(Debug, PartialEq)]
+pub struct Layout {
+    pub lines: Vec<LayoutLine>,
+    // Names of the entered submenus, outermost first, see Layout::for_each_title
+    pub titles: Vec<String>,
+    pub position: ListPosition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollMode {
+    // The window moves by whole pages
+    Paged,
+    // The window moves one line at a time, keeping margin lines between the selected line and
+    // the window edges where the list allows it
+    Continuous { margin: usize },
+}
+
+// Window of line_count lines over the displayed list. In continuous mode it remembers its
+// position between frames, so renderers keep one.
+pub struct Viewport {
+    line_count: usize,
+    scroll_mode: ScrollMode,
+    top_entry_idx: Cell<usize>,
+}
+
+impl Viewport {
+    pub fn new(line_count: usize) -> Viewport {
+        Viewport {
+            line_count,
+            scroll_mode: ScrollMode::Paged,
+            top_entry_idx: Cell::new(0),
+        }
+    }
+
+    pub fn get_line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub fn set_line_count(&mut self, line_count: usize) {
+        self.line_count = line_count;
+        self.top_entry_idx.set(0);
+    }
+
+    pub fn set_scroll_mode(&mut self, scroll_mode: ScrollMode) {
+        self.scroll_mode = scroll_mode;
+        self.top_entry_idx.set(0);
+    }
+
+    fn get_top_visible_entry_idx(&self, entry_count: usize, selected_entry_idx: usize) -> usize {
+        let line_count = self.line_count;
+        let top_entry_idx = match self.scroll_mode {
+            ScrollMode::Paged => selected_entry_idx.div_euclid(line_count) * line_count,
+            ScrollMode::Continuous { margin } => {
+                let margin = margin.min((line_count - 1) / 2);
+                // Moves as little as possible from the last position, which may belong to
+                // another list, e.g. after leaving a submenu
+                let min_top_entry_idx =
+                    (selected_entry_idx + margin + 1).saturating_sub(line_count);
+                let max_top_entry_idx = selected_entry_idx.saturating_sub(margin);
+                self.top_entry_idx
+                    .get()
+                    .clamp(min_top_entry_idx, max_top_entry_idx)
+                    .min(entry_count.saturating_sub(line_count))
+            }
+        };
+        self.top_entry_idx.set(top_entry_idx);
+        top_entry_idx
+    }
+}
+
+enum LabelSource<'a> {
+    Submenu(&'a SubmenuMenuItem),
+    Picker(&'a dyn PickerView),
+}
+
+// Line of the visible window whose label is written on demand, used to render without
+// allocating
+pub struct LineView<'a> {
+    label_source: &'a LabelSource<'a>,
+    entry_idx: usize,
+    pub line_idx: usize,
+    pub selection: LineSelection,
+    pub scroll_indicator: ScrollIndicator,
+    // Char idx in the label of the position being edited
+    pub cursor_position: Option<usize>,
+}
+
+impl LineView<'_> {
+    pub fn write_label(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        match self.label_source {
+            LabelSource::Submenu(submenu) => submenu.write_item_label(
+                writer,
+                self.entry_idx,
+                matches!(self.selection, LineSelection::Focused),
+            ),
+            LabelSource::Picker(picker_view) => {
+                picker_view.write_entry_label(writer, self.entry_idx)
+            }
+        }
+    }
+
+    pub fn write_name(&self, writer: &mut dyn Write) -> core::fmt::Result {
+        match self.label_source {
+            LabelSource::Submenu(submenu) => match submenu.get_item(self.entry_idx) {
+                Some(item) => item.write_name(writer),
+                None => self.write_label(writer),
+            },
+            LabelSource::Picker(_) => self.write_label(writer),
+        }
+    }
+
+    // Rows of a submenu source which aren't created are displayed as plain labels
+    pub fn get_kind(&self) -> ItemKind {
+        match self.label_source {
+            LabelSource::Submenu(submenu) => submenu
+                .get_item(self.entry_idx)
+                .map_or(ItemKind::Other, |item| {
+                    item.get_kind(matches!(self.selection, LineSelection::Focused))
+                }),
+            LabelSource::Picker(_) => ItemKind::Other,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        match self.label_source {
+            LabelSource::Submenu(submenu) => submenu
+                .get_item(self.entry_idx)
+                .is_none_or(|item| item.is_enabled()),
+            LabelSource::Picker(_) => true,
+        }
+    }
+}
+
+impl Layout {
+    // Paged window, see new_in_viewport for continuous scrolling
+    pub fn new(menu: &Menu, line_count: usize) -> Layout {
+        Self::new_in_viewport(menu, &Viewport::new(line_count))
+    }
+
+    pub fn new_in_viewport(menu: &Menu, viewport: &Viewport) -> Layout {
+        let mut lines = Vec::new();
+        Self::for_each_line(menu, viewport, |line_view| {
+            let mut label: String = String::new();
+            line_view.write_label(&mut label).unwrap();
+            let kind = line_view.get_kind();
+            let name = match kind {
+                ItemKind::Toggle(_) => {
+                    let mut name: String = String::new();
+                    line_view.write_name(&mut name).unwrap();
+                    Some(name)
+                }
+                _ => None,
+            };
+            lines.push(LayoutLine {
+                label,
+                selection: line_view.selection,
+                scroll_indicator: line_view.scroll_indicator,
+                cursor_position: line_view.cursor_position,
+                kind,
+                enabled: line_view.is_enabled(),
+                name,
+            });
+        });
+        let mut titles = Vec::new();
+        Self::for_each_title(menu, |item| {
+            let mut title: String = String::new();
+            item.write_name(&mut title).unwrap();
+            titles.push(title);
+        });
+        Layout {
+            lines,
+            titles,
+            position: Self::get_list_position(menu),
+        }
+    }
+
+    // Items whose entries are displayed full screen, i.e. entered submenus and the list
+    // item whose picker is open. The root menu is left out.
+    pub fn for_each_title(menu: &Menu, f: impl FnMut(&dyn MenuItem)) {
+        for_each_title(menu.get_submenu_menu_item(), f)
+    }
+
+    // Fails if there is no title at title_idx
+    pub fn write_title(menu: &Menu, writer: &mut dyn Write, title_idx: usize) -> core::fmt::Result {
+        let mut result = Err(core::fmt::Error);
+        let mut current_title_idx = 0;
+        Self::for_each_title(menu, |item| {
+            if current_title_idx == title_idx {
+                result = item.write_name(writer);
+            }
+            current_title_idx += 1;
+        });
+        result
+    }
+
+    pub fn get_title_count(menu: &Menu) -> usize {
+        let mut title_count = 0;
+        Self::for_each_title(menu, |_| title_count += 1);
+        title_count
+    }
+
+    pub fn get_list_position(menu: &Menu) -> ListPosition {
+        let submenu = get_visible_submenu(menu.get_submenu_menu_item());
+        let selected_item = submenu.get_selected_item();
+        match selected_item.get_picker_view() {
+            Some(picker_view) if submenu.is_focused() => ListPosition {
+                selected_entry_idx: picker_view.get_focused_entry_idx(),
+                entry_count: picker_view.entry_count(),
+            },
+            _ => ListPosition {
+                selected_entry_idx: submenu.get_selected_item_idx(),
+                entry_count: submenu.item_count(),
+            },
+        }
+    }
+
+    pub fn for_each_line(menu: &Menu, viewport: &Viewport, f: impl FnMut(&LineView)) {
+        let submenu = get_visible_submenu(menu.get_submenu_menu_item());
+        let is_focused = submenu.is_focused();
+        let selected_item = submenu.get_selected_item();
+        if is_focused {
+            if let Some(picker_view) = selected_item.get_picker_view() {
+                return Self::for_each_list_line(
+                    &LabelSource::Picker(picker_view),
+                    viewport,
+                    picker_view.entry_count(),
+                    picker_view.get_focused_entry_idx(),
+                    LineSelection::Selected,
+                    None,
+                    f,
+                );
+            }
+        }
+
+        let (selection, cursor_position) = if is_focused {
+            (
+                LineSelection::Focused,
+                selected_item.get_cursor_position(true),
+            )
+        } else {
+            (LineSelection::Selected, None)
+        };
+        Self::for_each_list_line(
+            &LabelSource::Submenu(submenu),
+            viewport,
+            submenu.item_count(),
+            submenu.get_selected_item_idx(),
+            selection,
+            cursor_position,
+            f,
+        )
+    }
+
+    // Pages through the entries the same way for submenus and pickers
+    fn for_each_list_line(
+        label_source: &LabelSource,
+        viewport: &Viewport,
+        entry_count: usize,
+        selected_entry_idx: usize,
+        selection: LineSelection,
+        cursor_position: Option<usize>,
+        mut f: impl FnMut(&LineView),
+    ) {
+        let line_count = viewport.get_line_count();
+        let top_visible_entry_idx =
+            viewport.get_top_visible_entry_idx(entry_count, selected_entry_idx);
+        let bottom_idx = core::cmp::min(line_count + top_visible_entry_idx, entry_count);
+        for entry_idx in top_visible_entry_idx..bottom_idx {
+            let is_selected_entry = entry_idx == selected_entry_idx;
+            f(&LineView {
+                label_source,
+                entry_idx,
+                line_idx: entry_idx - top_visible_entry_idx,
+                selection: if is_selected_entry {
+                    selection
+                } else {
+                    LineSelection::Unselected
+                },
+                scroll_indicator: get_scroll_indicator(
+                    line_count,
+                    entry_idx,
+                    top_visible_entry_idx,
+                    entry_count,
+                ),
+                cursor_position: cursor_position.filter(|_| is_selected_entry),
+            });
+        }
+    }
+}
+
+pub trait Renderer {
+    type Output;
+
+    fn get_viewport(&self) -> &Viewport;
+    // The tick is an application provided frame counter for animations, e.g. a marquee
+    fn render_layout(&self, layout: &Layout, tick: usize) -> Self::Output;
+
+    fn render_frame(&self, menu: &Menu, tick: usize) -> Self::Output {
+        self.render_layout(&Layout::new_in_viewport(menu, self.get_viewport()), tick)
+    }
+
+    fn render(&self, menu: &Menu) -> Self::Output {
+        self.render_frame(menu, 0)
+    }
+}
+
+// The deepest entered submenu, whose items are displayed
+fn get_visible_submenu(submenu: &SubmenuMenuItem) -> &SubmenuMenuItem {
+    if submenu.is_focused() {
+        if let Some(child_submenu) = submenu.get_selected_item().get_child_submenu() {
+            return get_visible_submenu(child_submenu);
+        }
+    }
+    submenu
+}
+
+// Walks the same path as get_visible_submenu, reporting the items it enters
+fn for_each_title(submenu: &SubmenuMenuItem, mut f: impl FnMut(&dyn MenuItem)) {
+    let mut submenu = submenu;
+    while submenu.is_focused() {
+        let selected_item = submenu.get_selected_item();
+        match selected_item.get_child_submenu() {
+            Some(child_submenu) => {
+                f(selected_item);
+                submenu = child_submenu;
+            }
+            None => {
+                if selected_item.get_picker_view().is_some() {
+                    f(selected_item);
+                }
+                break;
+            }
+        }
+    }
+}
+
+fn get_scroll_indicator(
+    line_count: usize,
+    entry_idx: usize,
+    top_visible_entry_idx: usize,
+    entry_count: usize,
+) -> ScrollIndicator {
+    let bottom_entry_idx = top_visible_entry_idx + line_count - 1;
+    if entry_idx == top_visible_entry_idx && top_visible_entry_idx != 0 {
+        ScrollIndicator::Up
+    } else if entry_idx == bottom_entry_idx && bottom_entry_idx < entry_count - 1 {
+        ScrollIndicator::Down
+    } else {
+        ScrollIndicator::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu_items::basic_menu_item::BasicMenuItem;
+    use crate::menu_items::menu_item::MenuItem;
+    use crate::menu_items::range_menu_item::{RangeEditMode, RangeMenuItem};
+
+    fn create_line(
+        label: &str,
+        selection: LineSelection,
+        scroll_indicator: ScrollIndicator,
+    ) -> LayoutLine {
+        LayoutLine {
+            label: String::from(label),
+            selection,
+            scroll_indicator,
+            cursor_position: None,
+            kind: ItemKind::Other,
+            enabled: true,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn layout_contains_visible_window() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+            Box::new(BasicMenuItem::new(String::from("Item3"))),
+        ];
+        let mut menu = Menu::new(items).unwrap();
+
+        assert_eq!(
+            Layout::new(&menu, 2).lines,
+            vec![
+                create_line("Item1", LineSelection::Selected, ScrollIndicator::None),
+                create_line("Item2", LineSelection::Unselected, ScrollIndicator::Down),
+            ]
+        );
+
+        menu.down();
+        menu.down();
+        assert_eq!(
+            Layout::new(&menu, 2).lines,
+            vec![create_line(
+                "Item3",
+                LineSelection::Selected,
+                ScrollIndicator::Up
+            )]
+        );
+    }
+
+    #[test]
+    fn layout_marks_focused_line_and_cursor() {
+        let mut range_item = RangeMenuItem::new(String::from("Volume"), 0, 100, 1).unwrap();
+        range_item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(range_item),
+        ];
+        let mut menu = Menu::new(items).unwrap();
+        menu.down();
+        menu.enter();
+
+        let layout = Layout::new(&menu, 2);
+        assert_eq!(layout.lines[0].cursor_position, None);
+        assert_eq!(layout.lines[1].label, "Volume: 000");
+        assert_eq!(layout.lines[1].selection, LineSelection::Focused);
+        assert_eq!(layout.lines[1].cursor_position, Some(10));
+    }
+
+    fn get_window(menu: &Menu, viewport: &Viewport) -> Vec<(String, ScrollIndicator)> {
+        Layout::new_in_viewport(menu, viewport)
+            .lines
+            .into_iter()
+            .map(|line| (line.label, line.scroll_indicator))
+            .collect()
+    }
+
+    #[test]
+    fn continuous_viewport_keeps_margin() {
+        let items: Vec<Box<dyn MenuItem>> = (1..=6)
+            .map(|item_idx| -> Box<dyn MenuItem> {
+                Box::new(BasicMenuItem::new(format!("Item{}", item_idx)))
+            })
+            .collect();
+        let mut menu = Menu::new(items).unwrap();
+        let mut viewport = Viewport::new(3);
+        viewport.set_scroll_mode(ScrollMode::Continuous { margin: 1 });
+
+        let labels = |menu: &Menu| -> Vec<String> {
+            get_window(menu, &viewport)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect()
+        };
+        assert_eq!(labels(&menu), vec!["Item1", "Item2", "Item3"]);
+        menu.down();
+        assert_eq!(labels(&menu), vec!["Item1", "Item2", "Item3"]);
+        menu.down();
+        assert_eq!(labels(&menu), vec!["Item2", "Item3", "Item4"]);
+        menu.down();
+        menu.down();
+        assert_eq!(labels(&menu), vec!["Item4", "Item5", "Item6"]);
+        // The last item can't keep the margin
+        menu.down();
+        assert_eq!(
+            get_window(&menu, &viewport),
+            vec![
+                (String::from("Item4"), ScrollIndicator::Up),
+                (String::from("Item5"), ScrollIndicator::None),
+                (String::from("Item6"), ScrollIndicator::None),
+            ]
+        );
+        menu.up();
+        menu.up();
+        assert_eq!(labels(&menu), vec!["Item3", "Item4", "Item5"]);
+        menu.up();
+        assert_eq!(
+            get_window(&menu, &viewport),
+            vec![
+                (String::from("Item2"), ScrollIndicator::Up),
+                (String::from("Item3"), ScrollIndicator::None),
+                (String::from("Item4"), ScrollIndicator::Down),
+            ]
+        );
+    }
+
+    #[test]
+    fn layout_contains_titles_and_position() {
+        let submenu = SubmenuMenuItem::new(
+            String::from("Display"),
+            vec![
+                Box::new(BasicMenuItem::new(String::from("Brightness"))),
+                Box::new(BasicMenuItem::new(String::from("Contrast"))),
+            ],
+        )
+        .unwrap();
+        let settings =
+            SubmenuMenuItem::new(String::from("Settings"), vec![Box::new(submenu)]).unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Status"))),
+            Box::new(settings),
+        ];
+        let mut menu = Menu::new(items).unwrap();
+
+        let layout = Layout::new(&menu, 2);
+        assert!(layout.titles.is_empty());
+        assert_eq!(
+            layout.position,
+            ListPosition {
+                selected_entry_idx: 0,
+                entry_count: 2
+            }
+        );
+
+        menu.down();
+        menu.enter();
+        menu.enter();
+        menu.down();
+        let layout = Layout::new(&menu, 2);
+        assert_eq!(layout.titles, vec!["Settings", "Display"]);
+        assert_eq!(
+            layout.position,
+            ListPosition {
+                selected_entry_idx: 1,
+                entry_count: 2
+            }
+        );
+        assert_eq!(Layout::get_title_count(&menu), 2);
+        let mut title: String = String::new();
+        Layout::write_title(&menu, &mut title, 1).unwrap();
+        assert_eq!(title, "Display");
+        assert!(Layout::write_title(&menu, &mut title, 2).is_err());
+    }
+}
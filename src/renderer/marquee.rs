@@ -0,0 +1,55 @@
+// Horizontal scrolling of the selected line if its label doesn't fit
+pub struct Marquee {
+    ticks_per_step: usize,
+    pause_ticks: usize,
+}
+
+impl Marquee {
+    // Scrolls one column every ticks_per_step ticks and stays still for pause_ticks at both ends
+    pub fn new(ticks_per_step: usize, pause_ticks: usize) -> Result<Marquee, &'static str> {
+        if ticks_per_step == 0 {
+            Err("Ticks per step must be bigger than zero")
+        } else {
+            Ok(Marquee {
+                ticks_per_step,
+                pause_ticks,
+            })
+        }
+    }
+
+    // Columns to skip at the given tick for a label overflow_width columns too long
+    pub(crate) fn get_offset(&self, overflow_width: usize, tick: usize) -> usize {
+        if overflow_width == 0 {
+            return 0;
+        }
+        // Every offset is shown for one step, the first and last one additionally for the pause
+        let period = 2 * self.pause_ticks + (overflow_width + 1) * self.ticks_per_step;
+        let period_tick = tick % period;
+        if period_tick < self.pause_ticks {
+            0
+        } else {
+            ((period_tick - self.pause_ticks) / self.ticks_per_step).min(overflow_width)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_fails_zero_ticks_per_step() {
+        assert_eq!(
+            Marquee::new(0, 5).err(),
+            Some("Ticks per step must be bigger than zero")
+        );
+    }
+
+    #[test]
+    fn offset_pauses_at_both_ends() {
+        let marquee = Marquee::new(2, 3).unwrap();
+        let offsets: Vec<usize> = (0..14).map(|tick| marquee.get_offset(2, tick)).collect();
+        assert_eq!(offsets, vec![0, 0, 0, 0, 0, 1, 1, 2, 2, 2, 2, 2, 0, 0]);
+        assert_eq!(marquee.get_offset(0, 5), 0);
+    }
+}
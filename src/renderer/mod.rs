@@ -1 +1,9 @@
+pub mod display_width;
+pub mod frame_buffer;
+pub mod glyph_registry;
+pub mod hd44780;
+pub mod header;
+pub mod layout;
+pub mod marquee;
 pub mod string_renderer;
+pub mod theme;
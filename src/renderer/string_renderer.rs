@@ -1,11 +1,63 @@
 use crate::menu::Menu;
-use crate::menu_items::menu_item::MenuItem;
-use crate::menu_items::submenu_menu_item::SubmenuMenuItem;
+use crate::menu_items::menu_item::ItemKind;
+use crate::renderer::display_width::{
+    char_width, skip_width, str_width, truncate_to_width, WidthCounter,
+};
+use crate::renderer::frame_buffer::{CellWriter, FrameBuffer, WIDE_CHAR_CONTINUATION};
+use crate::renderer::header::{Header, HeaderAlignment, HeaderTitle};
+use crate::renderer::layout::{
+    Layout, LayoutLine, LineSelection, ListPosition, Renderer, ScrollIndicator, ScrollMode,
+    Viewport,
+};
+use crate::renderer::marquee::Marquee;
+use crate::renderer::theme::{FocusStyle, Theme};
+use core::cell::Cell;
 use core::fmt::Write;
 
 pub struct StringRenderer {
     char_count: usize,
-    line_count: usize,
+    viewport: Viewport,
+    east_asian_width: bool,
+    marquee: Option<Marquee>,
+    // Title count and selected entry idx the marquee started scrolling at, and the tick
+    marquee_start: Cell<Option<((usize, usize), usize)>>,
+    theme: Theme,
+    header: Option<Header>,
+}
+
+// Writes a label with brackets around the char at the cursor position
+struct CursorMarker<'a> {
+    writer: &'a mut dyn Write,
+    cursor_position: usize,
+    brackets: (char, char),
+    char_idx: usize,
+}
+
+impl Write for CursorMarker<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            if self.char_idx == self.cursor_position {
+                self.writer.write_char(self.brackets.0)?;
+                self.writer.write_char(c)?;
+                self.writer.write_char(self.brackets.1)?;
+            } else {
+                self.writer.write_char(c)?;
+            }
+            self.char_idx += 1;
+        }
+        Ok(())
+    }
+}
+
+// Columns around the label of a line, as the theme sets them
+struct LineFrame {
+    first_char: char,
+    // Written right after the label, its column is reserved on every line
+    closing_bracket: Option<char>,
+    checkbox: Option<&'static str>,
+    submenu_marker: Option<char>,
+    scroll_char: Option<char>,
+    label_width: usize,
 }
 
 impl StringRenderer {
@@ -16,139 +68,502 @@ impl StringRenderer {
             Err("Invalid line count. At least 2 lines required.")
         } else {
             Ok(Self {
-                line_count,
+                viewport: Viewport::new(line_count),
                 char_count,
+                east_asian_width: false,
+                marquee: None,
+                marquee_start: Cell::new(None),
+                theme: Theme::default(),
+                header: None,
             })
         }
     }
 
-    pub fn render(&self, menu: &Menu) -> Vec<String> {
-        let submenu_item = menu.get_submenu_menu_item();
-        let mut lines = self
-            .generate_lines_to_render(submenu_item)
-            .unwrap_or_else(move || Vec::new());
+    // Counts East Asian wide chars as two columns, e.g. for terminals
+    pub fn set_east_asian_width(&mut self, east_asian_width: bool) {
+        self.east_asian_width = east_asian_width;
+    }
 
-        while lines.len() != self.line_count {
-            let mut line: String = String::new();
-            for _char_idx in 0..self.char_count {
-                line.push(' ');
+    // Scrolls the selected line if its label doesn't fit, see Renderer::render_frame. It starts
+    // over when the selection changes. Lines with a cursor, e.g. while editing digits, don't
+    // scroll.
+    pub fn set_marquee(&mut self, marquee: Marquee) {
+        self.marquee = Some(marquee);
+    }
+
+    pub fn set_scroll_mode(&mut self, scroll_mode: ScrollMode) {
+        self.viewport.set_scroll_mode(scroll_mode);
+    }
+
+    // E.g. Theme::ascii for displays without arrow glyphs
+    pub fn set_theme(&mut self, theme: Theme) -> Result<(), &'static str> {
+        if !theme.has_single_column_glyphs() {
+            return Err("Theme glyphs must be one column wide");
+        }
+        let reserved_column_count = 2
+            + usize::from(theme.focus_style == FocusStyle::Brackets)
+            + usize::from(theme.show_submenu_markers)
+            + usize::from(theme.show_scroll_column);
+        if reserved_column_count >= self.char_count {
+            return Err("Invalid char count. The theme leaves no column for labels.");
+        }
+        self.theme = theme;
+        Ok(())
+    }
+
+    // Takes the first line, the items are displayed in the remaining ones
+    pub fn set_header(&mut self, header: Header) -> Result<(), &'static str> {
+        if self.header.is_none() {
+            let line_count = self.viewport.get_line_count();
+            if line_count < 3 {
+                return Err("Invalid line count. At least 3 lines required for a header.");
             }
+            self.viewport.set_line_count(line_count - 1);
+        }
+        self.header = Some(header);
+        Ok(())
+    }
 
-            lines.push(line);
+    // Gives the header line back to the items
+    pub fn clear_header(&mut self) {
+        if self.header.take().is_some() {
+            let line_count = self.viewport.get_line_count();
+            self.viewport.set_line_count(line_count + 1);
         }
+    }
 
-        lines
+    fn get_header_line_count(&self) -> usize {
+        usize::from(self.header.is_some())
+    }
+
+    // Line and column of the position being edited, e.g. the active digit of a range item, to
+    // place the display's cursor. The rendered lines mark it with the cursor brackets of the
+    // theme, if any.
+    pub fn get_cursor_position(&self, menu: &Menu) -> Option<(usize, usize)> {
+        let layout = Layout::new_in_viewport(menu, &self.viewport);
+        let (line_idx, line, label_position) =
+            layout
+                .lines
+                .iter()
+                .enumerate()
+                .find_map(|(line_idx, line)| {
+                    line.cursor_position
+                        .map(|label_position| (line_idx, line, label_position))
+                })?;
+        let mut label_chars = line.label.chars();
+        let column: usize = label_chars
+            .by_ref()
+            .take(label_position)
+            .map(|c| char_width(c, self.east_asian_width))
+            .sum();
+        let mut cursor_width = label_chars
+            .next()
+            .map_or(1, |c| char_width(c, self.east_asian_width));
+        let column = match self.theme.cursor_brackets {
+            Some(_) => {
+                cursor_width += 1;
+                column + 1
+            }
+            None => column,
+        };
+        let line_frame = self.get_line_frame(
+            line.selection,
+            line.scroll_indicator,
+            line.kind,
+            line.enabled,
+        );
+        if column + cursor_width > line_frame.label_width {
+            None
+        } else {
+            Some((line_idx + self.get_header_line_count(), column + 1))
+        }
     }
 
-    fn generate_lines_to_render<'a>(&self, item: &dyn MenuItem) -> Option<Vec<String>> {
-        if let Some(sub_submenu) = item.as_any().downcast_ref::<SubmenuMenuItem>() {
-            let lines_from_item_option = if sub_submenu.is_focused() {
-                let selected_item = sub_submenu.get_selected_item();
-                let item_lines_to_render_option = self.generate_lines_to_render(selected_item);
-                if let Some(item_lines_to_render) = item_lines_to_render_option {
-                    Some(item_lines_to_render)
-                } else {
-                    None
+    // Same output as Renderer::render_frame, written into a reusable buffer without
+    // allocating. The buffer has to be char count wide and line count high. Labels are written
+    // once per frame, the selected one a second time while the marquee scrolls it.
+    pub fn render_into<const W: usize, const H: usize>(
+        &self,
+        menu: &Menu,
+        tick: usize,
+        frame: &mut FrameBuffer<W, H>,
+    ) -> Result<(), &'static str> {
+        let header_line_count = self.get_header_line_count();
+        if W != self.char_count || H != self.viewport.get_line_count() + header_line_count {
+            return Err("Frame buffer size must match char count and line count");
+        }
+        frame.clear();
+        let marquee_tick = self.get_marquee_tick(
+            Layout::get_title_count(menu),
+            Layout::get_list_position(menu).selected_entry_idx,
+            tick,
+        );
+        if let Some(header) = &self.header {
+            self.write_header(
+                frame.get_line_mut(0),
+                header,
+                Layout::get_title_count(menu),
+                &|writer, title_idx| Layout::write_title(menu, writer, title_idx),
+                Layout::get_list_position(menu),
+            );
+        }
+        Layout::for_each_line(menu, &self.viewport, |line_view| {
+            let line_frame = self.get_line_frame(
+                line_view.selection,
+                line_view.scroll_indicator,
+                line_view.get_kind(),
+                line_view.is_enabled(),
+            );
+            let write_content = |writer: &mut dyn Write| match line_frame.checkbox {
+                Some(checkbox) => {
+                    write!(writer, "{} ", checkbox)?;
+                    line_view.write_name(writer)
                 }
-            } else {
-                None
+                None => match (line_view.cursor_position, self.theme.cursor_brackets) {
+                    (Some(cursor_position), Some(brackets)) => {
+                        line_view.write_label(&mut CursorMarker {
+                            writer,
+                            cursor_position,
+                            brackets,
+                            char_idx: 0,
+                        })
+                    }
+                    _ => line_view.write_label(writer),
+                },
             };
 
-            if lines_from_item_option.is_some() {
-                lines_from_item_option
-            } else {
-                let mut lines_to_render: Vec<String> = Vec::new();
-                let selected_item_idx = sub_submenu.get_selected_item_idx();
-                let top_visible_item_idx = self.get_top_visible_item_idx(selected_item_idx);
-                let items_length = sub_submenu.item_count();
-                let bottom_idx =
-                    core::cmp::min(self.line_count + top_visible_item_idx, items_length);
-                for visible_item_idx in top_visible_item_idx..bottom_idx {
-                    let visible_item = sub_submenu.get_item(visible_item_idx).unwrap();
-                    let line_to_render = self.generate_submenu_line_to_render(
-                        &sub_submenu,
-                        visible_item_idx,
-                        visible_item,
-                    );
-                    lines_to_render.push(line_to_render);
+            let line = frame.get_line_mut(line_view.line_idx + header_line_count);
+            line[0] = line_frame.first_char;
+            let label_end = 1 + line_frame.label_width;
+            let label_cells = &mut line[1..label_end];
+            let mut writer = CellWriter::new(label_cells, 0, self.east_asian_width);
+            // The writer never fails and labels are best effort
+            let _ = write_content(&mut writer);
+            let mut label_column = writer.get_column();
+            let skip_columns = match (line_view.selection, line_view.cursor_position) {
+                (LineSelection::Unselected, _) | (_, Some(_)) => 0,
+                _ => self.get_marquee_offset(
+                    writer.get_width(),
+                    line_frame.label_width,
+                    marquee_tick,
+                ),
+            };
+            if skip_columns > 0 {
+                // Only a scrolled label is written a second time
+                label_cells.fill(' ');
+                let mut writer = CellWriter::new(label_cells, skip_columns, self.east_asian_width);
+                let _ = write_content(&mut writer);
+                label_column = writer.get_column();
+            }
+            let label_end = match line_frame.closing_bracket {
+                Some(closing_bracket) => {
+                    line[1 + label_column] = closing_bracket;
+                    label_end + 1
                 }
+                None => label_end,
+            };
+            if let Some(submenu_marker) = line_frame.submenu_marker {
+                line[label_end] = submenu_marker;
+            }
+            if let Some(scroll_char) = line_frame.scroll_char {
+                line[W - 1] = scroll_char;
+            }
+        });
+        Ok(())
+    }
 
-                Some(lines_to_render)
+    fn write_header(
+        &self,
+        cells: &mut [char],
+        header: &Header,
+        title_count: usize,
+        write_title: &dyn Fn(&mut dyn Write, usize) -> core::fmt::Result,
+        position: ListPosition,
+    ) {
+        let east_asian_width = self.east_asian_width;
+        let mut title_width = cells.len();
+        if header.show_position {
+            let write_position = |writer: &mut dyn Write| {
+                write!(
+                    writer,
+                    "{}/{}",
+                    position.selected_entry_idx + 1,
+                    position.entry_count
+                )
+            };
+            let mut width_counter = WidthCounter::new(east_asian_width);
+            let _ = write_position(&mut width_counter);
+            let position_width = width_counter.width.min(cells.len());
+            let position_column = cells.len() - position_width;
+            title_width = position_column.saturating_sub(1);
+            let position_cells = &mut cells[position_column..];
+            let _ = write_position(&mut CellWriter::new(position_cells, 0, east_asian_width));
+        }
+
+        let write_path = |writer: &mut dyn Write| match (title_count, header.title) {
+            (0, _) => writer.write_str(header.root_title),
+            (_, HeaderTitle::Submenu) => write_title(writer, title_count - 1),
+            (_, HeaderTitle::Breadcrumb) => {
+                for title_idx in 0..title_count {
+                    if title_idx != 0 {
+                        writer.write_str(self.theme.breadcrumb_separator)?;
+                    }
+                    write_title(writer, title_idx)?;
+                }
+                Ok(())
             }
+        };
+        let mut width_counter = WidthCounter::new(east_asian_width);
+        let _ = write_path(&mut width_counter);
+        let path_width = width_counter.width;
+        let title_cells = &mut cells[..title_width];
+        if path_width <= title_width {
+            let offset = match header.alignment {
+                HeaderAlignment::Left => 0,
+                HeaderAlignment::Center => (title_width - path_width) / 2,
+                HeaderAlignment::Right => title_width - path_width,
+            };
+            let _ = write_path(&mut CellWriter::new(
+                &mut title_cells[offset..],
+                0,
+                east_asian_width,
+            ));
+        } else if header.title == HeaderTitle::Breadcrumb {
+            // The innermost submenus are kept
+            let ellipsis_width = str_width(self.theme.ellipsis, east_asian_width).min(title_width);
+            let (ellipsis_cells, path_cells) = title_cells.split_at_mut(ellipsis_width);
+            let _ =
+                CellWriter::new(ellipsis_cells, 0, east_asian_width).write_str(self.theme.ellipsis);
+            let skip_columns = path_width - path_cells.len();
+            let _ = write_path(&mut CellWriter::new(
+                path_cells,
+                skip_columns,
+                east_asian_width,
+            ));
         } else {
-            None
+            let _ = write_path(&mut CellWriter::new(title_cells, 0, east_asian_width));
         }
     }
 
-    fn generate_submenu_line_to_render(
+    fn get_line_frame(
         &self,
-        submenu: &SubmenuMenuItem,
-        item_idx: usize,
-        item: &dyn MenuItem,
-    ) -> String {
-        let selected_item_idx = submenu.get_selected_item_idx();
-        let is_selected_item = item_idx == selected_item_idx;
-        let is_item_focused = is_selected_item && submenu.is_focused();
-        let selection_str: &str = if is_selected_item {
-            if is_item_focused {
-                "←"
-            } else {
-                "→"
-            }
-        } else {
-            " "
+        selection: LineSelection,
+        scroll_indicator: ScrollIndicator,
+        kind: ItemKind,
+        enabled: bool,
+    ) -> LineFrame {
+        let theme = &self.theme;
+        let brackets = match (theme.focus_style, selection) {
+            (FocusStyle::Brackets, LineSelection::Selected) => Some(theme.selected_brackets),
+            (FocusStyle::Brackets, LineSelection::Focused) => Some(theme.focused_brackets),
+            _ => None,
         };
-        let label = item.get_label(is_item_focused);
-        let max_length_label = self.char_count - 2;
-        let label_trimmed = if label.len() > max_length_label {
-            &label[..max_length_label]
+        let first_char = match (brackets, selection) {
+            (Some((opening_bracket, _)), _) => opening_bracket,
+            (None, LineSelection::Unselected) if !enabled => theme.disabled_marker,
+            (None, LineSelection::Unselected) => ' ',
+            (None, LineSelection::Selected) => theme.selected,
+            (None, LineSelection::Focused) => theme.focused,
+        };
+        let checkbox = match kind {
+            ItemKind::Toggle(true) if theme.show_checkboxes => Some(theme.checked),
+            ItemKind::Toggle(false) if theme.show_checkboxes => Some(theme.unchecked),
+            _ => None,
+        };
+        let submenu_marker = match kind {
+            ItemKind::Submenu if theme.show_submenu_markers => Some(theme.submenu_marker),
+            _ => None,
+        };
+        let scroll_char = if theme.show_scroll_column {
+            Some(match scroll_indicator {
+                ScrollIndicator::None => ' ',
+                ScrollIndicator::Up => theme.scroll_up,
+                ScrollIndicator::Down => theme.scroll_down,
+            })
         } else {
-            &label
+            None
         };
+        let label_width = self.char_count
+            - 1
+            - usize::from(theme.focus_style == FocusStyle::Brackets)
+            - usize::from(submenu_marker.is_some())
+            - usize::from(scroll_char.is_some());
+        LineFrame {
+            first_char,
+            closing_bracket: brackets.map(|(_, closing_bracket)| closing_bracket),
+            checkbox,
+            submenu_marker,
+            scroll_char,
+            label_width,
+        }
+    }
 
-        let top_visible_item_idx = self.get_top_visible_item_idx(selected_item_idx);
-        let bottom_item_idx = top_visible_item_idx + self.line_count - 1;
-        let arrow_str: &str = if item_idx == top_visible_item_idx {
-            if top_visible_item_idx != 0 {
-                "↑"
-            } else {
-                " "
+    // Ticks since the selection changed
+    fn get_marquee_tick(
+        &self,
+        title_count: usize,
+        selected_entry_idx: usize,
+        tick: usize,
+    ) -> usize {
+        if self.marquee.is_none() {
+            return tick;
+        }
+        let selection = (title_count, selected_entry_idx);
+        match self.marquee_start.get() {
+            Some((start_selection, start_tick))
+                if start_selection == selection && tick >= start_tick =>
+            {
+                tick - start_tick
             }
-        } else if item_idx == bottom_item_idx {
-            if bottom_item_idx < submenu.item_count() - 1 {
-                "↓"
-            } else {
-                " "
+            _ => {
+                self.marquee_start.set(Some((selection, tick)));
+                0
+            }
+        }
+    }
+
+    fn get_marquee_offset(&self, content_width: usize, label_width: usize, tick: usize) -> usize {
+        match &self.marquee {
+            Some(marquee) => marquee.get_offset(content_width.saturating_sub(label_width), tick),
+            None => 0,
+        }
+    }
+
+    fn generate_line(&self, layout_line: &LayoutLine, tick: usize) -> String {
+        let line_frame = self.get_line_frame(
+            layout_line.selection,
+            layout_line.scroll_indicator,
+            layout_line.kind,
+            layout_line.enabled,
+        );
+        let mut marked_content: String = String::new();
+        let content = match (line_frame.checkbox, &layout_line.name) {
+            (Some(checkbox), Some(name)) => {
+                marked_content = format!("{} {}", checkbox, name);
+                marked_content.as_str()
+            }
+            _ => match (layout_line.cursor_position, self.theme.cursor_brackets) {
+                (Some(cursor_position), Some(brackets)) => {
+                    let _ = CursorMarker {
+                        writer: &mut marked_content,
+                        cursor_position,
+                        brackets,
+                        char_idx: 0,
+                    }
+                    .write_str(&layout_line.label);
+                    marked_content.as_str()
+                }
+                _ => layout_line.label.as_str(),
+            },
+        };
+        let (label, skipped_too_much) = match (layout_line.selection, layout_line.cursor_position) {
+            (LineSelection::Unselected, _) | (_, Some(_)) => (content, 0),
+            _ => {
+                let offset = self.get_marquee_offset(
+                    str_width(content, self.east_asian_width),
+                    line_frame.label_width,
+                    tick,
+                );
+                skip_width(content, offset, self.east_asian_width)
             }
-        } else {
-            " "
         };
+        let (label_trimmed, label_width) = truncate_to_width(
+            label,
+            line_frame.label_width - skipped_too_much,
+            self.east_asian_width,
+        );
 
         let mut line_str: String = String::new();
-        write!(
-            line_str,
-            "{}{:3$}{}",
-            selection_str, label_trimmed, arrow_str, max_length_label
-        )
-        .unwrap();
+        line_str.push(line_frame.first_char);
+        for _column_idx in 0..skipped_too_much {
+            line_str.push(' ');
+        }
+        line_str.push_str(label_trimmed);
+        let mut padding_width = line_frame.label_width - skipped_too_much - label_width;
+        if let Some(closing_bracket) = line_frame.closing_bracket {
+            line_str.push(closing_bracket);
+        } else if self.theme.focus_style == FocusStyle::Brackets {
+            padding_width += 1;
+        }
+        for _column_idx in 0..padding_width {
+            line_str.push(' ');
+        }
+        if let Some(submenu_marker) = line_frame.submenu_marker {
+            line_str.push(submenu_marker);
+        }
+        if let Some(scroll_char) = line_frame.scroll_char {
+            line_str.push(scroll_char);
+        }
         line_str
     }
+}
+
+impl Renderer for StringRenderer {
+    type Output = Vec<String>;
+
+    fn get_viewport(&self) -> &Viewport {
+        &self.viewport
+    }
 
-    fn get_top_visible_item_idx(&self, selected_item_idx: usize) -> usize {
-        let div = selected_item_idx.div_euclid(self.line_count);
-        div * self.line_count
+    fn render_layout(&self, layout: &Layout, tick: usize) -> Vec<String> {
+        let marquee_tick = self.get_marquee_tick(
+            layout.titles.len(),
+            layout.position.selected_entry_idx,
+            tick,
+        );
+        let mut lines: Vec<String> = layout
+            .lines
+            .iter()
+            .map(|layout_line| self.generate_line(layout_line, marquee_tick))
+            .collect();
+
+        while lines.len() != self.viewport.get_line_count() {
+            let mut line: String = String::new();
+            for _char_idx in 0..self.char_count {
+                line.push(' ');
+            }
+
+            lines.push(line);
+        }
+
+        if let Some(header) = &self.header {
+            let mut cells = vec![' '; self.char_count];
+            self.write_header(
+                &mut cells,
+                header,
+                layout.titles.len(),
+                &|writer, title_idx| writer.write_str(&layout.titles[title_idx]),
+                layout.position,
+            );
+            let header_line = cells
+                .into_iter()
+                .filter(|c| *c != WIDE_CHAR_CONTINUATION)
+                .collect();
+            lines.insert(0, header_line);
+        }
+
+        lines
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keyboard::KeyboardKey;
     use crate::menu_items::action_menu_item::ActionMenuItem;
     use crate::menu_items::basic_menu_item::BasicMenuItem;
-    use crate::menu_items::list_menu_item::ListMenuItem;
-    use crate::menu_items::range_menu_item::RangeMenuItem;
+    use crate::menu_items::checklist_menu_item::{ChecklistMenuItem, ChecklistSummary};
+    use crate::menu_items::list_menu_item::{ListEditMode, ListMenuItem};
+    use crate::menu_items::menu_item::MenuItem;
+    use crate::menu_items::radio_menu_item::RadioMenuItem;
+    use crate::menu_items::range_menu_item::{RangeEditMode, RangeMenuItem};
+    use crate::menu_items::submenu_menu_item::{SubmenuMenuItem, SubmenuSource};
     use crate::menu_items::toggle_menu_item::ToggleMenuItem;
-    use std::cell::RefCell;
+    use crate::menu_items::value_menu_item::ValueMenuItem;
+    use crate::renderer::glyph_registry::{GlyphRegistry, ARROW_DOWN, CHECKED_BOX};
+    use crate::renderer::hd44780::{CharacterRom, Hd44780Encoder};
+    use crate::renderer::theme::Theme;
+    use std::cell::{Cell, RefCell};
     use std::rc::Rc;
 
     #[test]
@@ -189,7 +604,7 @@ mod tests {
         assert_eq!(lines_to_render[3], " Item4          ");
         assert_eq!(lines_to_render[4], " Item5         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 5);
         assert_eq!(lines_to_render[0], " Item1          ");
@@ -198,7 +613,7 @@ mod tests {
         assert_eq!(lines_to_render[3], " Item4          ");
         assert_eq!(lines_to_render[4], " Item5         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 5);
         assert_eq!(lines_to_render[0], " Item1          ");
@@ -207,7 +622,7 @@ mod tests {
         assert_eq!(lines_to_render[3], " Item4          ");
         assert_eq!(lines_to_render[4], " Item5         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 5);
         assert_eq!(lines_to_render[0], " Item1          ");
@@ -216,7 +631,7 @@ mod tests {
         assert_eq!(lines_to_render[3], "→Item4          ");
         assert_eq!(lines_to_render[4], " Item5         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 5);
         assert_eq!(lines_to_render[0], " Item1          ");
@@ -225,7 +640,7 @@ mod tests {
         assert_eq!(lines_to_render[3], " Item4          ");
         assert_eq!(lines_to_render[4], "→Item5         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 5);
         assert_eq!(lines_to_render[0], "→Item6         ↑");
@@ -253,37 +668,37 @@ mod tests {
         assert_eq!(lines_to_render[0], "→Item1          ");
         assert_eq!(lines_to_render[1], " Item2         ↓");
 
-        assert_eq!(menu.up(), false);
+        assert!(!menu.up());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1          ");
         assert_eq!(lines_to_render[1], " Item2         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], " Item1          ");
         assert_eq!(lines_to_render[1], "→Item2         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item3         ↑");
         assert_eq!(lines_to_render[1], " Item4         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], " Item3         ↑");
         assert_eq!(lines_to_render[1], "→Item4         ↓");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item5         ↑");
         assert_eq!(lines_to_render[1], "                ");
 
-        assert_eq!(menu.up(), true);
+        assert!(menu.up());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], " Item3         ↑");
@@ -325,7 +740,7 @@ mod tests {
         assert_eq!(lines_to_render[0], "→Item1          ");
         assert_eq!(lines_to_render[1], "                ");
 
-        assert_eq!(false, menu.enter());
+        assert!(!menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1          ");
@@ -357,7 +772,7 @@ mod tests {
         assert_eq!(lines_to_render[1], " Item2          ");
         assert_eq!(*clicked_count.borrow(), 0);
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1          ");
@@ -386,44 +801,44 @@ mod tests {
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1: Elem1   ");
         assert_eq!(lines_to_render[1], " Item2          ");
-        assert_eq!(menu.left(), false);
-        assert_eq!(menu.right(), false);
+        assert!(!menu.left());
+        assert!(!menu.right());
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: Elem1   ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
         // Can't move while focused
-        assert_eq!(menu.up(), false);
-        assert_eq!(menu.down(), false);
+        assert!(!menu.up());
+        assert!(!menu.down());
 
-        assert_eq!(menu.right(), true);
+        assert!(menu.right());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: Elem2   ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1: Elem1   ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: Elem1   ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.left(), true);
+        assert!(menu.left());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: Elem3   ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1: Elem3   ");
@@ -446,13 +861,13 @@ mod tests {
         assert_eq!(lines_to_render[0], "→Item1: OFF     ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1: ON      ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1: OFF     ");
@@ -475,31 +890,31 @@ mod tests {
         assert_eq!(lines_to_render[0], "→Item1: 3       ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: 3       ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.left(), true);
+        assert!(menu.left());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: 10      ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.left(), true);
+        assert!(menu.left());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: 9       ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.right(), true);
+        assert!(menu.right());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "←Item1: 10      ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1: 10      ");
@@ -531,46 +946,775 @@ mod tests {
         assert_eq!(lines_to_render[0], "→Item1          ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Sub Item1: OFF ");
         assert_eq!(lines_to_render[1], " Sub Item2      ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Sub Item1: ON  ");
         assert_eq!(lines_to_render[1], " Sub Item2      ");
 
-        assert_eq!(menu.down(), true);
+        assert!(menu.down());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], " Sub Item1: ON  ");
         assert_eq!(lines_to_render[1], "→Sub Item2      ");
 
-        assert_eq!(menu.enter(), true);
+        assert!(menu.enter());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Sub2 Item1     ");
         assert_eq!(lines_to_render[1], "                ");
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], " Sub Item1: ON  ");
         assert_eq!(lines_to_render[1], "→Sub Item2      ");
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1          ");
         assert_eq!(lines_to_render[1], " Item2          ");
 
-        assert_eq!(menu.back(), true);
+        assert!(menu.back());
         let lines_to_render = renderer.render(&menu);
         assert_eq!(lines_to_render.len(), 2);
         assert_eq!(lines_to_render[0], "→Item1          ");
         assert_eq!(lines_to_render[1], " Item2          ");
     }
+
+    #[test]
+    fn range_item_with_unit_fits_line() {
+        let mut range_menu_item: RangeMenuItem<i8> =
+            RangeMenuItem::new(String::from("Temp"), -30, 30, 1).unwrap();
+        range_menu_item.set_unit(String::from("°C"));
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(range_menu_item),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let renderer = StringRenderer::new(12, 2).unwrap();
+
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Temp: -30° ");
+        assert_eq!(lines_to_render[1], " Item2      ");
+
+        assert!(menu.enter());
+        assert!(menu.right());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "←Temp: -29° ");
+        assert_eq!(lines_to_render[1], " Item2      ");
+    }
+
+    #[test]
+    fn range_item_cursor_marks_active_digit() {
+        let mut range_menu_item: RangeMenuItem =
+            RangeMenuItem::new(String::from("Freq"), 0, 999999, 1).unwrap();
+        range_menu_item
+            .set_edit_mode(RangeEditMode::Digits)
+            .unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+            Box::new(range_menu_item),
+        ];
+
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(16, 2).unwrap();
+        let mut frame: FrameBuffer<16, 2> = FrameBuffer::new();
+        assert!(menu.down());
+        assert!(menu.down());
+        assert_eq!(renderer.get_cursor_position(&menu), None);
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "←Freq: 00000[0]↑");
+        assert_eq!(renderer.get_cursor_position(&menu), Some((0, 13)));
+
+        assert!(menu.left());
+        assert!(menu.up());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "←Freq: 0000[1]0↑");
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        assert_eq!(frame.line_to_string(0).unwrap(), "←Freq: 0000[1]0↑");
+        assert_eq!(renderer.get_cursor_position(&menu), Some((0, 12)));
+
+        // Left to the display's cursor
+        renderer
+            .set_theme(Theme {
+                cursor_brackets: None,
+                ..Theme::unicode()
+            })
+            .unwrap();
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "←Freq: 000010  ↑");
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        assert_eq!(frame.line_to_string(0).unwrap(), "←Freq: 000010  ↑");
+        assert_eq!(renderer.get_cursor_position(&menu), Some((0, 11)));
+
+        assert!(menu.enter());
+        assert_eq!(renderer.get_cursor_position(&menu), None);
+    }
+
+    #[test]
+    fn checklist_item_is_usable() {
+        let entries = vec![
+            String::from("Monday"),
+            String::from("Tuesday"),
+            String::from("Wednesday"),
+        ];
+        let mut checklist_menu_item =
+            ChecklistMenuItem::new(String::from("Days"), entries).unwrap();
+        checklist_menu_item.set_summary(ChecklistSummary::Fit(18));
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(checklist_menu_item),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let renderer = StringRenderer::new(20, 2).unwrap();
+
+        assert!(menu.enter());
+        assert!(menu.enter());
+        assert!(menu.right());
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "←Days: [x] Tuesday  ");
+        assert_eq!(lines_to_render[1], " Item2              ");
+
+        assert!(!menu.down());
+        assert!(menu.right());
+        assert!(menu.right());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "←Days: OK           ");
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Days: 2 selected   ");
+
+        assert!(menu.enter());
+        assert!(menu.right());
+        assert!(menu.enter());
+        assert!(menu.left());
+        assert!(menu.left());
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Days: Monday       ");
+    }
+
+    #[test]
+    fn radio_item_is_usable() {
+        let entries = vec![
+            String::from("Auto"),
+            String::from("Heat"),
+            String::from("Cool"),
+        ];
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(RadioMenuItem::new(String::from("Mode"), entries).unwrap()),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let renderer = StringRenderer::new(16, 2).unwrap();
+
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Mode: Auto     ");
+        assert_eq!(lines_to_render[1], " Item2          ");
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→(*) Auto       ");
+        assert_eq!(lines_to_render[1], " ( ) Heat      ↓");
+
+        assert!(menu.down());
+        assert!(menu.down());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→( ) Cool      ↑");
+        assert_eq!(lines_to_render[1], "                ");
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Mode: Cool     ");
+        assert_eq!(lines_to_render[1], " Item2          ");
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→(*) Cool      ↑");
+
+        assert!(menu.back());
+        assert!(menu.down());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], " Mode: Cool     ");
+        assert_eq!(lines_to_render[1], "→Item2          ");
+    }
+
+    #[test]
+    fn list_picker_is_rendered_full_screen() {
+        let list_entries = vec![
+            String::from("Berlin"),
+            String::from("Lisbon"),
+            String::from("London"),
+            String::from("Madrid"),
+            String::from("Moscow"),
+        ];
+        let mut list_item = ListMenuItem::new(String::from("Zone"), list_entries).unwrap();
+        list_item.set_edit_mode(ListEditMode::Picker);
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(list_item),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let renderer = StringRenderer::new(16, 2).unwrap();
+
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Zone: Berlin   ");
+        assert_eq!(lines_to_render[1], " Item2          ");
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Berlin         ");
+        assert_eq!(lines_to_render[1], " Lisbon        ↓");
+        assert_eq!(renderer.get_cursor_position(&menu), None);
+
+        assert!(menu.press(KeyboardKey::new(None, Some(b"m".to_vec()))));
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], " London        ↑");
+        assert_eq!(lines_to_render[1], "→Madrid        ↓");
+
+        assert!(menu.down());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Moscow        ↑");
+        assert_eq!(lines_to_render[1], "                ");
+
+        assert!(menu.enter());
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Zone: Moscow   ");
+        assert_eq!(lines_to_render[1], " Item2          ");
+    }
+
+    struct RecordSource {
+        label_count: Rc<Cell<usize>>,
+    }
+
+    impl SubmenuSource for RecordSource {
+        fn count(&self) -> usize {
+            10_000
+        }
+
+        fn get_label(&self, idx: usize) -> String {
+            self.label_count.set(self.label_count.get() + 1);
+            format!("Record {}", idx)
+        }
+
+        fn create_item(&self, idx: usize) -> Box<dyn MenuItem> {
+            Box::new(BasicMenuItem::new(format!("Record {}", idx)))
+        }
+    }
+
+    #[test]
+    fn submenu_source_renders_visible_rows_only() {
+        let label_count = Rc::new(Cell::new(0));
+        let source = RecordSource {
+            label_count: Rc::clone(&label_count),
+        };
+        let items: Vec<Box<dyn MenuItem>> = vec![Box::new(SubmenuMenuItem::new_with_source(
+            String::from("Records"),
+            Box::new(source),
+        ))];
+
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let renderer = StringRenderer::new(16, 2).unwrap();
+
+        assert!(menu.enter());
+        assert!(menu.down());
+        assert!(menu.down());
+        label_count.set(0);
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Record 2      ↑");
+        assert_eq!(lines_to_render[1], " Record 3      ↓");
+        assert_eq!(label_count.get(), 1);
+    }
+
+    #[test]
+    fn non_ascii_labels_are_truncated_and_padded() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Größe"))),
+            Box::new(BasicMenuItem::new(String::from("Température"))),
+        ];
+
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let renderer = StringRenderer::new(10, 2).unwrap();
+
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→Größe    ");
+        assert_eq!(lines_to_render[1], " Températ ");
+        assert_eq!(lines_to_render[1].chars().count(), 10);
+    }
+
+    #[test]
+    fn wide_chars_take_two_columns() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("温度设定值"))),
+            Box::new(BasicMenuItem::new(String::from("A温"))),
+        ];
+
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(8, 2).unwrap();
+        renderer.set_east_asian_width(true);
+
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→温度设 ");
+        assert_eq!(lines_to_render[1], " A温    ");
+
+        renderer.set_east_asian_width(false);
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "→温度设定值  ");
+    }
+
+    #[test]
+    fn marquee_scrolls_selected_line() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Calibration"))),
+            Box::new(BasicMenuItem::new(String::from("Long label 2"))),
+        ];
+
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(10, 2).unwrap();
+        renderer.set_marquee(Marquee::new(2, 3).unwrap());
+
+        let lines_to_render = renderer.render_frame(&menu, 0);
+        assert_eq!(lines_to_render[0], "→Calibrat ");
+        assert_eq!(lines_to_render[1], " Long lab ");
+        let lines_to_render = renderer.render_frame(&menu, 4);
+        assert_eq!(lines_to_render[0], "→Calibrat ");
+        let lines_to_render = renderer.render_frame(&menu, 5);
+        assert_eq!(lines_to_render[0], "→alibrati ");
+        assert_eq!(lines_to_render[1], " Long lab ");
+        let lines_to_render = renderer.render_frame(&menu, 9);
+        assert_eq!(lines_to_render[0], "→ibration ");
+        let lines_to_render = renderer.render_frame(&menu, 13);
+        assert_eq!(lines_to_render[0], "→ibration ");
+        let lines_to_render = renderer.render_frame(&menu, 14);
+        assert_eq!(lines_to_render[0], "→Calibrat ");
+        assert_eq!(renderer.render(&menu)[0], "→Calibrat ");
+    }
+
+    #[test]
+    fn marquee_restarts_on_selection_change() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Calibration"))),
+            Box::new(BasicMenuItem::new(String::from("Long label 2"))),
+        ];
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(10, 2).unwrap();
+        renderer.set_marquee(Marquee::new(1, 0).unwrap());
+        assert_eq!(renderer.render_frame(&menu, 10)[0], "→Calibrat ");
+        assert_eq!(renderer.render_frame(&menu, 12)[0], "→libratio ");
+
+        assert!(menu.down());
+        assert_eq!(renderer.render_frame(&menu, 13)[1], "→Long lab ");
+        assert_eq!(renderer.render_frame(&menu, 14)[1], "→ong labe ");
+    }
+
+    #[test]
+    fn lines_with_cursor_dont_scroll() {
+        let mut range_item = RangeMenuItem::new(String::from("Frequency"), 0, 999999, 1).unwrap();
+        range_item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(range_item),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(16, 2).unwrap();
+        renderer.set_marquee(Marquee::new(1, 0).unwrap());
+        let mut frame: FrameBuffer<16, 2> = FrameBuffer::new();
+        assert!(menu.enter());
+        for _ in 0..5 {
+            assert!(menu.left());
+        }
+        for tick in 0..4 {
+            assert_eq!(renderer.render_frame(&menu, tick)[0], "←Frequency: [0] ");
+            renderer.render_into(&menu, tick, &mut frame).unwrap();
+            assert_eq!(frame.line_to_string(0).unwrap(), "←Frequency: [0] ");
+            assert_eq!(renderer.get_cursor_position(&menu), Some((0, 13)));
+        }
+    }
+
+    #[test]
+    fn marquee_keeps_wide_chars_whole() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("温度设定值"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(8, 2).unwrap();
+        renderer.set_east_asian_width(true);
+        renderer.set_marquee(Marquee::new(1, 0).unwrap());
+
+        assert_eq!(renderer.render_frame(&menu, 0)[0], "→温度设 ");
+        assert_eq!(renderer.render_frame(&menu, 1)[0], "→ 度设  ");
+        assert_eq!(renderer.render_frame(&menu, 2)[0], "→度设定 ");
+        assert_eq!(renderer.render_frame(&menu, 4)[0], "→设定值 ");
+    }
+
+    #[test]
+    fn render_into_matches_render() {
+        let mut range_item = RangeMenuItem::new(String::from("Volume"), 0, 100, 1).unwrap();
+        range_item.set_edit_mode(RangeEditMode::Digits).unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("温度设定值"))),
+            Box::new(BasicMenuItem::new(String::from("Température"))),
+            Box::new(range_item),
+        ];
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(8, 2).unwrap();
+        renderer.set_east_asian_width(true);
+        renderer.set_marquee(Marquee::new(1, 0).unwrap());
+        let mut frame: FrameBuffer<8, 2> = FrameBuffer::new();
+
+        let mut assert_same_output = |menu: &Menu| {
+            for tick in 0..8 {
+                renderer.render_into(menu, tick, &mut frame).unwrap();
+                let frame_lines: Vec<String> = (0..2)
+                    .map(|line_idx| frame.line_to_string(line_idx).unwrap())
+                    .collect();
+                assert_eq!(frame_lines, renderer.render_frame(menu, tick));
+            }
+        };
+        assert_same_output(&menu);
+        menu.down();
+        assert_same_output(&menu);
+        menu.down();
+        menu.enter();
+        assert_same_output(&menu);
+
+        let mut wrong_frame: FrameBuffer<16, 2> = FrameBuffer::new();
+        assert!(renderer.render_into(&menu, 0, &mut wrong_frame).is_err());
+    }
+
+    #[test]
+    fn render_into_reads_values_once_unless_scrolled() {
+        let read_count = Rc::new(Cell::new(0));
+        let provider_read_count = Rc::clone(&read_count);
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(ValueMenuItem::new(
+                String::from("Temperature"),
+                Box::new(move || {
+                    provider_read_count.set(provider_read_count.get() + 1);
+                    21
+                }),
+            )),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+        ];
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(20, 2).unwrap();
+        renderer.set_marquee(Marquee::new(1, 0).unwrap());
+        let mut frame: FrameBuffer<20, 2> = FrameBuffer::new();
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        assert_eq!(read_count.get(), 1);
+
+        let renderer = StringRenderer::new(8, 2).unwrap();
+        let mut frame: FrameBuffer<8, 2> = FrameBuffer::new();
+        read_count.set(0);
+        renderer.render_into(&menu, 3, &mut frame).unwrap();
+        assert_eq!(read_count.get(), 1);
+    }
+
+    #[test]
+    fn ascii_indicators_encode_on_every_rom() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Item1"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+            Box::new(BasicMenuItem::new(String::from("Item3"))),
+        ];
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(8, 2).unwrap();
+        let a00_encoder = Hd44780Encoder::new(CharacterRom::A00);
+        let a02_encoder = Hd44780Encoder::new(CharacterRom::A02);
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(
+            a00_encoder.encode(&lines_to_render[1]).unmappable_chars,
+            vec!['↓']
+        );
+        assert!(a02_encoder
+            .encode(&lines_to_render[1])
+            .unmappable_chars
+            .is_empty());
+
+        assert!(renderer
+            .set_theme(Theme {
+                selected: '温',
+                ..Theme::ascii()
+            })
+            .is_err());
+        renderer.set_theme(Theme::ascii()).unwrap();
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], ">Item1  ");
+        assert_eq!(lines_to_render[1], " Item2 v");
+        for line in lines_to_render {
+            assert!(a00_encoder.encode(&line).unmappable_chars.is_empty());
+        }
+    }
+
+    #[test]
+    fn custom_glyphs_replace_indicators_and_label_chars() {
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("☑ Wifi"))),
+            Box::new(BasicMenuItem::new(String::from("Item2"))),
+            Box::new(BasicMenuItem::new(String::from("Item3"))),
+        ];
+        let menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(10, 2).unwrap();
+        renderer
+            .set_theme(Theme {
+                scroll_down: '▾',
+                ..Theme::ascii()
+            })
+            .unwrap();
+        let mut registry = GlyphRegistry::new();
+        registry.register('☑', CHECKED_BOX).unwrap();
+        registry.register('▾', ARROW_DOWN).unwrap();
+
+        let mut lines_to_render = renderer.render(&menu);
+        let uploads = registry.substitute_lines(&mut lines_to_render).unwrap();
+        assert_eq!(uploads.len(), 2);
+        assert_eq!(lines_to_render[0], ">\u{8} Wifi   ");
+        assert_eq!(lines_to_render[1], " Item2   \u{9}");
+        let encoder = Hd44780Encoder::new(CharacterRom::A00);
+        assert!(encoder
+            .encode(&lines_to_render[1])
+            .unmappable_chars
+            .is_empty());
+    }
+
+    #[test]
+    fn theme_options_are_rendered() {
+        let mut action_item = ActionMenuItem::new(String::from("Reset"), Box::new(|| true));
+        action_item.set_enabled(false);
+        let submenu = SubmenuMenuItem::new(
+            String::from("Network"),
+            vec![Box::new(BasicMenuItem::new(String::from("Item")))],
+        )
+        .unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(ToggleMenuItem::new(String::from("Wifi"))),
+            Box::new(submenu),
+            Box::new(action_item),
+        ];
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(12, 3).unwrap();
+        renderer
+            .set_theme(Theme {
+                show_checkboxes: true,
+                show_submenu_markers: true,
+                ..Theme::ascii()
+            })
+            .unwrap();
+        assert_eq!(
+            renderer.render(&menu),
+            vec![">[ ] Wifi   ", " Network  > ", "xReset      "]
+        );
+        menu.enter();
+        assert_eq!(renderer.render(&menu)[0], ">[x] Wifi   ");
+
+        renderer
+            .set_theme(Theme {
+                focus_style: FocusStyle::Brackets,
+                show_scroll_column: false,
+                ..Theme::ascii()
+            })
+            .unwrap();
+        assert_eq!(
+            renderer.render(&menu),
+            vec!["[Wifi: ON]  ", " Network    ", "xReset      "]
+        );
+        let mut frame: FrameBuffer<12, 3> = FrameBuffer::new();
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        assert_eq!(frame.line_to_string(0).unwrap(), "[Wifi: ON]  ");
+
+        renderer
+            .set_theme(Theme {
+                focus_style: FocusStyle::Brackets,
+                show_checkboxes: true,
+                show_submenu_markers: true,
+                ..Theme::unicode()
+            })
+            .unwrap();
+        menu.down();
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(
+            lines_to_render,
+            vec![" ☑ Wifi     ", "[Network] › ", "×Reset      "]
+        );
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        for (line_idx, line) in lines_to_render.iter().enumerate() {
+            assert_eq!(&frame.line_to_string(line_idx).unwrap(), line);
+        }
+
+        let mut small_renderer = StringRenderer::new(4, 2).unwrap();
+        assert!(small_renderer
+            .set_theme(Theme {
+                focus_style: FocusStyle::Brackets,
+                show_submenu_markers: true,
+                ..Theme::ascii()
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn continuous_scrolling_moves_one_line() {
+        let items: Vec<Box<dyn MenuItem>> = (1..=8)
+            .map(|item_idx| -> Box<dyn MenuItem> {
+                Box::new(BasicMenuItem::new(format!("Item{}", item_idx)))
+            })
+            .collect();
+        let mut menu: Menu = Menu::new(items).unwrap();
+
+        let mut renderer = StringRenderer::new(8, 5).unwrap();
+        renderer.set_scroll_mode(ScrollMode::Continuous { margin: 1 });
+        for _item_idx in 0..5 {
+            menu.down();
+        }
+        assert_eq!(
+            renderer.render(&menu),
+            vec![" Item3 ↑", " Item4  ", " Item5  ", "→Item6  ", " Item7 ↓"]
+        );
+        let mut frame: FrameBuffer<8, 5> = FrameBuffer::new();
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        assert_eq!(frame.line_to_string(0).unwrap(), " Item3 ↑");
+
+        menu.up();
+        assert_eq!(renderer.render(&menu)[0], " Item3 ↑");
+        menu.down();
+        menu.down();
+        assert_eq!(renderer.render(&menu)[0], " Item4 ↑");
+        assert_eq!(renderer.render(&menu)[4], " Item8  ");
+    }
+
+    fn create_nested_menu() -> Menu {
+        let display = SubmenuMenuItem::new(
+            String::from("Display"),
+            vec![
+                Box::new(BasicMenuItem::new(String::from("Brightness"))),
+                Box::new(BasicMenuItem::new(String::from("Contrast"))),
+                Box::new(BasicMenuItem::new(String::from("Timeout"))),
+            ],
+        )
+        .unwrap();
+        let settings =
+            SubmenuMenuItem::new(String::from("Settings"), vec![Box::new(display)]).unwrap();
+        let items: Vec<Box<dyn MenuItem>> = vec![
+            Box::new(BasicMenuItem::new(String::from("Status"))),
+            Box::new(settings),
+        ];
+        Menu::new(items).unwrap()
+    }
+
+    #[test]
+    fn header_shows_submenu_and_position() {
+        let mut menu = create_nested_menu();
+        let mut renderer = StringRenderer::new(20, 4).unwrap();
+        let mut header = Header::new(HeaderTitle::Submenu, "Main");
+        header.show_position = true;
+        renderer.set_header(header).unwrap();
+
+        assert_eq!(
+            renderer.render(&menu),
+            vec![
+                "Main             1/2",
+                "→Status             ",
+                " Settings           ",
+                "                    "
+            ]
+        );
+        menu.down();
+        menu.enter();
+        menu.enter();
+        menu.down();
+        menu.down();
+        assert_eq!(
+            renderer.render(&menu),
+            vec![
+                "Display          3/3",
+                " Brightness         ",
+                " Contrast           ",
+                "→Timeout            "
+            ]
+        );
+
+        header.alignment = HeaderAlignment::Center;
+        header.show_position = false;
+        renderer.set_header(header).unwrap();
+        assert_eq!(renderer.render(&menu)[0], "      Display       ");
+        header.alignment = HeaderAlignment::Right;
+        renderer.set_header(header).unwrap();
+        assert_eq!(renderer.render(&menu)[0], "             Display");
+        assert_eq!(renderer.render(&menu).len(), 4);
+
+        renderer.clear_header();
+        renderer.clear_header();
+        assert_eq!(
+            renderer.render(&menu),
+            vec![
+                " Brightness         ",
+                " Contrast           ",
+                "→Timeout            ",
+                "                    "
+            ]
+        );
+
+        let mut small_renderer = StringRenderer::new(20, 2).unwrap();
+        assert!(small_renderer.set_header(header).is_err());
+    }
+
+    #[test]
+    fn header_breadcrumb_is_cut_off_at_the_start() {
+        let mut menu = create_nested_menu();
+        menu.down();
+        menu.enter();
+        menu.enter();
+
+        let mut renderer = StringRenderer::new(20, 3).unwrap();
+        let mut header = Header::new(HeaderTitle::Breadcrumb, "Main");
+        renderer.set_header(header).unwrap();
+        assert_eq!(renderer.render(&menu)[0], "Settings › Display  ");
+
+        header.show_position = true;
+        renderer.set_header(header).unwrap();
+        renderer.set_theme(Theme::ascii()).unwrap();
+        let lines_to_render = renderer.render(&menu);
+        assert_eq!(lines_to_render[0], "..ings > Display 1/3");
+
+        let mut frame: FrameBuffer<20, 3> = FrameBuffer::new();
+        renderer.render_into(&menu, 0, &mut frame).unwrap();
+        for (line_idx, line) in lines_to_render.iter().enumerate() {
+            assert_eq!(&frame.line_to_string(line_idx).unwrap(), line);
+        }
+    }
 }
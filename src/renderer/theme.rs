@@ -0,0 +1,110 @@
+use crate::renderer::display_width::char_width;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FocusStyle {
+    // Selection glyph in the first column
+    Marker,
+    // Selected label between brackets, e.g. "[Item]"
+    Brackets,
+}
+
+// Glyphs and options of StringRenderer. The options are off in the built-in themes, which
+// only differ in their glyphs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub selected: char,
+    pub focused: char,
+    pub scroll_up: char,
+    pub scroll_down: char,
+    pub selected_brackets: (char, char),
+    pub focused_brackets: (char, char),
+    pub checked: &'static str,
+    pub unchecked: &'static str,
+    pub submenu_marker: char,
+    pub breadcrumb_separator: &'static str,
+    // Replaces the cut off start of a breadcrumb
+    pub ellipsis: &'static str,
+    // Replaces the blank first column of disabled items
+    pub disabled_marker: char,
+    // Around the position being edited, e.g. "Freq: 01[2]0". None if the display's cursor
+    // shows it, see StringRenderer::get_cursor_position.
+    pub cursor_brackets: Option<(char, char)>,
+    pub focus_style: FocusStyle,
+    // Toggles are displayed as "[x] Label" instead of "Label: ON"
+    pub show_checkboxes: bool,
+    pub show_submenu_markers: bool,
+    // Without it the label can use the last column
+    pub show_scroll_column: bool,
+}
+
+impl Theme {
+    pub fn unicode() -> Theme {
+        Theme {
+            selected: '→',
+            focused: '←',
+            scroll_up: '↑',
+            scroll_down: '↓',
+            selected_brackets: ('[', ']'),
+            focused_brackets: ('«', '»'),
+            checked: "☑",
+            unchecked: "☐",
+            submenu_marker: '›',
+            breadcrumb_separator: " › ",
+            ellipsis: "…",
+            disabled_marker: '×',
+            cursor_brackets: Some(('[', ']')),
+            focus_style: FocusStyle::Marker,
+            show_checkboxes: false,
+            show_submenu_markers: false,
+            show_scroll_column: true,
+        }
+    }
+
+    // Available on every character ROM
+    pub fn ascii() -> Theme {
+        Theme {
+            selected: '>',
+            focused: '<',
+            scroll_up: '^',
+            scroll_down: 'v',
+            selected_brackets: ('[', ']'),
+            focused_brackets: ('<', '>'),
+            checked: "[x]",
+            unchecked: "[ ]",
+            submenu_marker: '>',
+            breadcrumb_separator: " > ",
+            ellipsis: "..",
+            disabled_marker: 'x',
+            ..Self::unicode()
+        }
+    }
+
+    // Each glyph has a column of its own, also with East Asian width
+    pub(crate) fn has_single_column_glyphs(&self) -> bool {
+        [
+            self.selected,
+            self.focused,
+            self.scroll_up,
+            self.scroll_down,
+            self.selected_brackets.0,
+            self.selected_brackets.1,
+            self.focused_brackets.0,
+            self.focused_brackets.1,
+            self.submenu_marker,
+            self.disabled_marker,
+        ]
+        .iter()
+        .chain(
+            self.cursor_brackets
+                .iter()
+                .flat_map(|(opening, closing)| [opening, closing]),
+        )
+        .all(|glyph| char_width(*glyph, true) == 1)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::unicode()
+    }
+}